@@ -0,0 +1,350 @@
+pub mod jsdoc;
+pub mod sourcemap;
+
+/// Error returned when code can't be generated.
+#[derive(Debug)]
+pub enum CodegenError {
+    /// Writing the generated code failed.
+    IoError(std::io::Error),
+    /// A statement can't be generated (eg. a regex literal with invalid flags).
+    InvalidStatement {
+        /// Description of what is wrong with the statement.
+        context: String
+    },
+    /// A name is not a valid identifier (eg. a reserved word used as a label).
+    InvalidIdentifier(String),
+    /// An operator is not a valid JavaScript operator.
+    InvalidOperator(String),
+    /// An `await` expression is used outside of an async function.
+    AwaitOutsideAsync,
+    /// A `yield` expression is used outside of a generator function.
+    YieldOutsideGenerator,
+}
+
+impl std::fmt::Display for CodegenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CodegenError::IoError(error) => write!(f, "I/O error: {}", error),
+            CodegenError::InvalidStatement { context } => write!(f, "Invalid statement: {}", context),
+            CodegenError::InvalidIdentifier(name) => write!(f, "Invalid identifier `{}`", name),
+            CodegenError::InvalidOperator(operator) => write!(f, "Invalid operator `{}`", operator),
+            CodegenError::AwaitOutsideAsync => write!(f, "`await` used outside of an async function"),
+            CodegenError::YieldOutsideGenerator => write!(f, "`yield` used outside of a generator function")
+        }
+    }
+}
+
+impl std::error::Error for CodegenError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CodegenError::IoError(error) => Some(error),
+            _ => None
+        }
+    }
+}
+
+impl From<std::io::Error> for CodegenError {
+    fn from(error: std::io::Error) -> Self {
+        CodegenError::IoError(error)
+    }
+}
+
+/// Write generated code for a `Display` implementation.
+/// `Display` can't report a `CodegenError`, so code that can't be generated is written as a comment
+/// describing the error instead (use `generate` to handle the error).
+pub(crate) fn display_generated(f: &mut std::fmt::Formatter<'_>, code: Result<String, CodegenError>) -> std::fmt::Result {
+    match code {
+        Ok(code) => f.write_str(&code),
+        Err(error) => write!(f, "/* {} */", error.to_string().replace("*/", "* /"))
+    }
+}
+
+/// Options controlling how code is generated.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CodegenOptions {
+    /// Quotes used for string literals and import paths.
+    pub quote_style: QuoteStyle,
+    /// When to terminate statements with semicolons.
+    pub semicolons: SemicolonMode,
+    /// Characters used for one level of indentation.
+    pub indent_style: IndentStyle,
+    /// Whether to strip all unnecessary whitespace.
+    pub minify: bool,
+    /// Kind of output to generate.
+    pub mode: GenerationMode,
+    /// Whether `Module::generate` also writes a source map file.
+    pub source_map: bool,
+    /// Preferred maximum line length, calls longer than this are broken with one argument per line.
+    /// A call is measured from the indentation of its statement, code before it on the same line isn't counted.
+    /// Lines are not broken if `None` or when minifying.
+    pub line_width: Option<usize>,
+}
+
+impl CodegenOptions {
+    /// Create new default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the quotes used for string literals and import paths.
+    pub fn with_quote_style(mut self, quote_style: QuoteStyle) -> Self {
+        self.quote_style = quote_style;
+        self
+    }
+
+    /// Set when to terminate statements with semicolons.
+    pub fn with_semicolons(mut self, semicolons: SemicolonMode) -> Self {
+        self.semicolons = semicolons;
+        self
+    }
+
+    /// Set the characters used for one level of indentation.
+    pub fn with_indent_style(mut self, indent_style: IndentStyle) -> Self {
+        self.indent_style = indent_style;
+        self
+    }
+
+    /// Set whether to strip all unnecessary whitespace.
+    pub fn with_minify(mut self, minify: bool) -> Self {
+        self.minify = minify;
+        self
+    }
+
+    /// Set the kind of output to generate.
+    pub fn with_mode(mut self, mode: GenerationMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set whether `Module::generate` also writes a source map file.
+    pub fn with_source_map(mut self, source_map: bool) -> Self {
+        self.source_map = source_map;
+        self
+    }
+
+    /// Set the preferred maximum line length (see `line_width`).
+    pub fn with_line_width(mut self, line_width: Option<usize>) -> Self {
+        self.line_width = line_width;
+        self
+    }
+
+    /// Check if code indented to the given level exceeds the preferred line length.
+    pub(crate) fn exceeds_line_width(&self, indent: usize, code: &str) -> bool {
+        match self.line_width {
+            Some(line_width) if !self.minify => {
+                let indent = self.indent(indent).len();
+                code.lines().any(|line| indent + line.len() > line_width)
+            },
+            _ => false
+        }
+    }
+
+    /// Check if a TypeScript declaration file is generated.
+    pub fn is_declaration(&self) -> bool {
+        self.mode == GenerationMode::TypeScriptDeclaration
+    }
+
+    /// Get the indentation string for the given level.
+    pub fn indent(&self, level: usize) -> String {
+        if self.minify {
+            return String::new();
+        }
+        match self.indent_style {
+            IndentStyle::Spaces(width) => " ".repeat(width * level),
+            IndentStyle::Tabs => "\t".repeat(level)
+        }
+    }
+
+    /// Get the terminator of class fields, interface members and expression statements.
+    /// Minified members are on one line, so they are always terminated.
+    pub(crate) fn member_terminator(&self) -> &'static str {
+        match self.semicolons {
+            SemicolonMode::Never if !self.minify => "",
+            _ => ";"
+        }
+    }
+
+    /// Get the line break placed between statements.
+    pub fn newline(&self) -> &'static str {
+        if self.minify { "" } else { "\n" }
+    }
+
+    /// Get the optional space placed between tokens.
+    pub fn space(&self) -> &'static str {
+        if self.minify { "" } else { " " }
+    }
+
+    /// Join tokens with spaces.
+    /// When minifying, spaces are only kept where the tokens would otherwise merge.
+    pub(crate) fn join_tokens(&self, tokens: &[&str]) -> String {
+        let mut code = String::new();
+        for token in tokens.iter().filter(|token| !token.is_empty()) {
+            if let (Some(last), Some(next)) = (code.chars().last(), token.chars().next()) {
+                if !self.minify || tokens_merge(last, next) {
+                    code.push(' ');
+                }
+            }
+            code.push_str(token);
+        }
+        code
+    }
+}
+
+/// Quotes used for generated strings.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum QuoteStyle {
+    #[default]
+    Single,
+    Double,
+    Backtick
+}
+
+impl QuoteStyle {
+    /// Get the quote character.
+    pub fn char(&self) -> char {
+        match self {
+            QuoteStyle::Single => '\'',
+            QuoteStyle::Double => '"',
+            QuoteStyle::Backtick => '`'
+        }
+    }
+
+    /// Escape the string and wrap it in quotes.
+    pub fn quote(&self, value: &str) -> String {
+        let quote = self.char();
+        format!("{}{}{}", quote, escape_string(value, quote), quote)
+    }
+
+    /// Escape the string and wrap it in quotes where a template literal isn't allowed (eg. import paths),
+    /// backticks fall back to single quotes.
+    pub fn quote_literal(&self, value: &str) -> String {
+        match self {
+            QuoteStyle::Backtick => QuoteStyle::Single.quote(value),
+            quote_style => quote_style.quote(value)
+        }
+    }
+}
+
+/// When to terminate statements with semicolons.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SemicolonMode {
+    /// Every statement is terminated, including raw code.
+    Always,
+    /// No statement is terminated, relying on automatic semicolon insertion.
+    Never,
+    /// Only statements that need it are terminated (not raw code, blocks or classes).
+    #[default]
+    AsNeeded
+}
+
+/// Kind of output produced by the code generator.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GenerationMode {
+    /// Regular code with implementations.
+    #[default]
+    JavaScript,
+    /// Regular code with implementations, written to `.ts` files.
+    TypeScript,
+    /// TypeScript declaration file (`.d.ts`), implementations are replaced by signatures.
+    TypeScriptDeclaration
+}
+
+impl GenerationMode {
+    /// Get the extension of files containing the output (eg. `js` or `d.ts`).
+    pub fn extension(&self) -> &'static str {
+        match self {
+            GenerationMode::JavaScript => "js",
+            GenerationMode::TypeScript => "ts",
+            GenerationMode::TypeScriptDeclaration => "d.ts"
+        }
+    }
+}
+
+/// Characters used for indentation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IndentStyle {
+    /// The given number of spaces per level.
+    Spaces(usize),
+    /// One tab per level.
+    Tabs
+}
+
+impl Default for IndentStyle {
+    fn default() -> Self {
+        IndentStyle::Spaces(4)
+    }
+}
+
+/// Check if two adjacent characters would merge into one token without a space between them.
+fn tokens_merge(last: char, next: char) -> bool {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    (is_word(last) && is_word(next))
+        || (last == next && matches!(last, '+' | '-' | '/'))
+}
+
+/// Escape a string so it can be placed between the given quotes.
+/// Characters above U+FFFF are written as `\u{...}` code point escapes.
+pub(crate) fn escape_string(value: &str, quote: char) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            // `\0` followed by a digit would be read as an octal escape.
+            '\0' if chars.peek().is_some_and(|next| next.is_ascii_digit()) => escaped.push_str("\\x00"),
+            '\0' => escaped.push_str("\\0"),
+            // Template literals would otherwise interpolate `${...}`.
+            '$' if quote == '`' && chars.peek() == Some(&'{') => escaped.push_str("\\$"),
+            c if c == quote => {
+                escaped.push('\\');
+                escaped.push(c);
+            },
+            c if c.is_control() || c == '\u{2028}' || c == '\u{2029}' => {
+                escaped.push_str(&format!("\\u{:04x}", c as u32));
+            },
+            c if c as u32 > 0xFFFF => escaped.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => escaped.push(c)
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codegen::{CodegenOptions, IndentStyle, QuoteStyle};
+
+    #[test]
+    fn test_quote_styles() {
+        assert_eq!(QuoteStyle::Single.quote("it's"), "'it\\'s'");
+        assert_eq!(QuoteStyle::Double.quote("say \"hi\""), "\"say \\\"hi\\\"\"");
+        assert_eq!(QuoteStyle::Backtick.quote("`${x}` costs $5"), "`\\`\\${x}\\` costs $5`");
+        assert_eq!(QuoteStyle::Backtick.quote_literal("it's"), "'it\\'s'");
+        assert_eq!(QuoteStyle::Double.quote_literal("it's"), "\"it's\"");
+    }
+
+    #[test]
+    fn test_indent_styles() {
+        assert_eq!(CodegenOptions::default().indent(2), "        ");
+        assert_eq!(CodegenOptions::new().with_indent_style(IndentStyle::Spaces(2)).indent(2), "    ");
+        assert_eq!(CodegenOptions::new().with_indent_style(IndentStyle::Tabs).indent(2), "\t\t");
+    }
+
+    #[test]
+    fn test_join_tokens() {
+        let options = CodegenOptions::new();
+        assert_eq!(options.join_tokens(&["let", "x", "=", "1"]), "let x = 1");
+
+        let options = CodegenOptions::new().with_minify(true);
+        assert_eq!(options.join_tokens(&["let", "x", "=", "1"]), "let x=1");
+        assert_eq!(options.join_tokens(&["a", "-", "-1"]), "a- -1");
+        assert_eq!(options.join_tokens(&["a", "in", "b"]), "a in b");
+    }
+}
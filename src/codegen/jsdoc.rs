@@ -0,0 +1,119 @@
+use crate::module::block::{comment_lines, Statement};
+
+/// Builder for JSDoc comments (eg. `/** @param {number} a */`).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct JsDoc {
+    /// Free text written above the tags.
+    pub description: Option<String>,
+    /// Tags in the order they were added, without the ` * ` prefix.
+    pub tags: Vec<String>,
+}
+
+impl JsDoc {
+    /// Create a new empty JSDoc comment.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the description written above the tags.
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// Add a `@param {type} name description` tag.
+    pub fn param(self, name: &str, param_type: &str, description: &str) -> Self {
+        self.tag(format!("@param {{{}}} {}", param_type, name), description)
+    }
+
+    /// Add a `@returns {type} description` tag.
+    pub fn returns(self, return_type: &str, description: &str) -> Self {
+        self.tag(format!("@returns {{{}}}", return_type), description)
+    }
+
+    /// Add a `@template name` tag.
+    pub fn template(self, name: &str) -> Self {
+        self.tag(format!("@template {}", name), "")
+    }
+
+    /// Add a `@throws {type} description` tag.
+    pub fn throws(self, error_type: &str, description: &str) -> Self {
+        self.tag(format!("@throws {{{}}}", error_type), description)
+    }
+
+    /// Add a `@see link` tag.
+    pub fn see(self, link: &str) -> Self {
+        self.tag(format!("@see {}", link), "")
+    }
+
+    fn tag(mut self, tag: String, description: &str) -> Self {
+        if description.is_empty() {
+            self.tags.push(tag);
+        } else {
+            self.tags.push(format!("{} {}", tag, description));
+        }
+        self
+    }
+
+    /// Get the text of the comment without the `/** */` delimiters.
+    pub fn text(&self) -> String {
+        let mut lines = Vec::new();
+        if let Some(description) = &self.description {
+            lines.push(description.clone());
+            if !self.tags.is_empty() {
+                lines.push(String::new());
+            }
+        }
+        lines.extend(self.tags.iter().cloned());
+        lines.join("\n")
+    }
+
+    /// Create the full `/** ... */` comment.
+    pub fn render(&self) -> String {
+        format!("/**\n{} */", comment_lines(&self.text().replace("*/", "*\\/"), ""))
+    }
+}
+
+impl From<JsDoc> for Statement {
+    fn from(value: JsDoc) -> Self {
+        Statement::DocComment(value.text())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codegen::jsdoc::JsDoc;
+    use crate::module::block::Block;
+
+    #[test]
+    fn test_jsdoc() {
+        let doc = JsDoc::new()
+            .description("Find an item by its key.")
+            .template("T")
+            .param("items", "T[]", "Items to search")
+            .param("key", "string", "")
+            .returns("T | undefined", "The found item")
+            .throws("TypeError", "If `items` is not an array")
+            .see("https://example.com/docs");
+
+        let mut block = Block::new(0);
+        block.stmt(doc.into()).unwrap();
+        block.raw("function find(items, key) {}");
+        assert_eq!(
+            block.generate().unwrap(),
+            concat!(
+                "/**\n",
+                " * Find an item by its key.\n",
+                " *\n",
+                " * @template T\n",
+                " * @param {T[]} items Items to search\n",
+                " * @param {string} key\n",
+                " * @returns {T | undefined} The found item\n",
+                " * @throws {TypeError} If `items` is not an array\n",
+                " * @see https://example.com/docs\n",
+                " */\n",
+                "function find(items, key) {}\n"
+            )
+        );
+    }
+}
@@ -0,0 +1,246 @@
+/// Location in an original source file that generated code comes from.
+/// Lines and columns are zero-based.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SourceLocation {
+    /// Path of the original source file.
+    pub source: String,
+    /// Line in the original source.
+    pub line: u32,
+    /// Column in the original source.
+    pub column: u32,
+    /// Original name of the symbol at the location (eg. a variable that was renamed).
+    pub name: Option<String>,
+}
+
+impl SourceLocation {
+    /// Create a new location without a name.
+    pub fn new(source: &str, line: u32, column: u32) -> Self {
+        Self {
+            source: source.to_string(),
+            line,
+            column,
+            name: None,
+        }
+    }
+
+    /// Set the original name of the symbol at the location.
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+}
+
+/// Mapping from a position in the generated code to a location in an original source.
+/// Lines and columns are zero-based, generated columns are counted in UTF-16 code units.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mapping {
+    /// Line in the generated code.
+    pub generated_line: u32,
+    /// Column in the generated code.
+    pub generated_col: u32,
+    /// Path of the original source file.
+    pub source: String,
+    /// Line in the original source.
+    pub original_line: u32,
+    /// Column in the original source.
+    pub original_col: u32,
+    /// Original name of the symbol at the position.
+    pub name: Option<String>,
+}
+
+impl Mapping {
+    /// Create a new mapping from the generated position to the original location.
+    pub fn new(generated_line: u32, generated_col: u32, location: &SourceLocation) -> Self {
+        Self {
+            generated_line,
+            generated_col,
+            source: location.source.clone(),
+            original_line: location.line,
+            original_col: location.column,
+            name: location.name.clone(),
+        }
+    }
+}
+
+/// Receiver of the mappings recorded while code is generated.
+pub trait SourceMapSink {
+    /// Record a mapping of the generated code.
+    fn add_mapping(&mut self, mapping: Mapping);
+}
+
+/// Source map (version 3) accumulating the mappings of generated code.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SourceMap {
+    /// Name of the generated file the source map belongs to.
+    pub file: Option<String>,
+    /// Recorded mappings in the order they were added.
+    pub mappings: Vec<Mapping>,
+}
+
+impl SourceMap {
+    /// Create a new empty source map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the paths of all original sources in the order they are first used.
+    pub fn sources(&self) -> Vec<&str> {
+        let mut sources = Vec::new();
+        for mapping in &self.mappings {
+            if !sources.contains(&mapping.source.as_str()) {
+                sources.push(mapping.source.as_str());
+            }
+        }
+        sources
+    }
+
+    /// Get all original names in the order they are first used.
+    pub fn names(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+        for name in self.mappings.iter().filter_map(|mapping| mapping.name.as_deref()) {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    /// Encode the mappings as the `mappings` field of a source map
+    /// (groups of VLQ encoded segments separated by `;` for each generated line).
+    pub fn encode_mappings(&self) -> String {
+        let sources = self.sources();
+        let names = self.names();
+        let mut mappings = self.mappings.iter().collect::<Vec<_>>();
+        mappings.sort_by_key(|mapping| (mapping.generated_line, mapping.generated_col));
+
+        let mut encoded = String::new();
+        let mut line = 0;
+        // Fields other than the generated column are relative to the previous segment in the whole map.
+        let (mut previous_col, mut previous_source, mut previous_line, mut previous_original_col, mut previous_name) = (0, 0, 0, 0, 0);
+        for (i, mapping) in mappings.iter().enumerate() {
+            if mapping.generated_line > line {
+                for _ in line..mapping.generated_line {
+                    encoded.push(';');
+                }
+                line = mapping.generated_line;
+                previous_col = 0;
+            } else if i > 0 {
+                encoded.push(',');
+            }
+
+            let source = sources.iter().position(|source| *source == mapping.source).unwrap_or_default() as i64;
+            encode_vlq(mapping.generated_col as i64 - previous_col, &mut encoded);
+            encode_vlq(source - previous_source, &mut encoded);
+            encode_vlq(mapping.original_line as i64 - previous_line, &mut encoded);
+            encode_vlq(mapping.original_col as i64 - previous_original_col, &mut encoded);
+            if let Some(name) = &mapping.name {
+                let name = names.iter().position(|other| other == name).unwrap_or_default() as i64;
+                encode_vlq(name - previous_name, &mut encoded);
+                previous_name = name;
+            }
+            previous_col = mapping.generated_col as i64;
+            previous_source = source;
+            previous_line = mapping.original_line as i64;
+            previous_original_col = mapping.original_col as i64;
+        }
+        encoded
+    }
+
+    /// Create the JSON representation of the source map.
+    pub fn to_json(&self) -> String {
+        let list = |values: Vec<&str>| values.into_iter().map(json_string).collect::<Vec<_>>().join(",");
+        let file = match &self.file {
+            Some(file) => format!("\"file\":{},", json_string(file)),
+            None => String::new()
+        };
+        format!(
+            "{{\"version\":3,{}\"sources\":[{}],\"names\":[{}],\"mappings\":{}}}",
+            file,
+            list(self.sources()),
+            list(self.names()),
+            json_string(&self.encode_mappings())
+        )
+    }
+}
+
+impl SourceMapSink for SourceMap {
+    fn add_mapping(&mut self, mapping: Mapping) {
+        self.mappings.push(mapping);
+    }
+}
+
+const BASE64_CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Append the base64 VLQ encoding of the value (as used by source maps) to the output.
+pub fn encode_vlq(value: i64, output: &mut String) {
+    // The lowest bit is the sign, followed by the absolute value in groups of 5 bits.
+    let mut value = (value.unsigned_abs() << 1) | (value < 0) as u64;
+    loop {
+        let mut digit = (value & 0b11111) as usize;
+        value >>= 5;
+        if value > 0 {
+            digit |= 0b100000;
+        }
+        output.push(BASE64_CHARS[digit] as char);
+        if value == 0 {
+            break;
+        }
+    }
+}
+
+/// Get the zero-based line and column (in UTF-16 code units) right after the code,
+/// relative to the given starting position.
+pub(crate) fn end_position(start: (u32, u32), code: &str) -> (u32, u32) {
+    match code.rsplit_once('\n') {
+        Some((before, last)) => (start.0 + before.matches('\n').count() as u32 + 1, last.encode_utf16().count() as u32),
+        None => (start.0, start.1 + code.encode_utf16().count() as u32)
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c)
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codegen::sourcemap::{encode_vlq, Mapping, SourceLocation, SourceMap, SourceMapSink};
+
+    #[test]
+    fn test_encode_vlq() {
+        let encode = |value| {
+            let mut output = String::new();
+            encode_vlq(value, &mut output);
+            output
+        };
+        assert_eq!(encode(0), "A");
+        assert_eq!(encode(1), "C");
+        assert_eq!(encode(-1), "D");
+        assert_eq!(encode(16), "gB");
+        assert_eq!(encode(123), "2H");
+        assert_eq!(encode(-1000), "x+B");
+    }
+
+    #[test]
+    fn test_source_map_json() {
+        let mut source_map = SourceMap::new();
+        source_map.file = Some("out.js".to_string());
+        source_map.add_mapping(Mapping::new(0, 0, &SourceLocation::new("main.src", 0, 0)));
+        source_map.add_mapping(Mapping::new(0, 4, &SourceLocation::new("main.src", 0, 4).with_name("count")));
+        source_map.add_mapping(Mapping::new(2, 2, &SourceLocation::new("lib.src", 10, 0)));
+        assert_eq!(
+            source_map.to_json(),
+            "{\"version\":3,\"file\":\"out.js\",\"sources\":[\"main.src\",\"lib.src\"],\"names\":[\"count\"],\"mappings\":\"AAAA,IAAIA;;ECUJ\"}"
+        );
+    }
+}
@@ -1,2 +1,3 @@
+pub mod codegen;
 pub mod module;
 pub mod macros;
\ No newline at end of file
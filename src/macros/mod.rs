@@ -4,8 +4,17 @@ macro_rules! import {
     ({ $($import:ident),+ } from $path:literal) => {
         $crate::module::Dependency::new(vec![$(stringify!($import).to_string()),+], $path)
     };
-    ($import:ident from $path:literal) => {
-        $crate::module::Dependency::new(vec![stringify!($import).to_string()], $path)
+    ($path:literal) => {
+        $crate::module::Dependency::side_effect($path)
+    };
+    (* as $alias:ident from $path:literal) => {
+        $crate::module::Dependency::namespace(stringify!($alias), $path)
+    };
+    ($default:ident, { $($import:ident),+ } from $path:literal) => {
+        $crate::module::Dependency::default_with_named(stringify!($default), vec![$(stringify!($import).to_string()),+], $path)
+    };
+    ($default:ident from $path:literal) => {
+        $crate::module::Dependency::default(stringify!($default), $path)
     };
 }
 
@@ -14,43 +23,89 @@ macro_rules! import {
 macro_rules! var {
     // Let variable
     (let $name:ident = $initializer:expr) => {
-        $crate::module::block::Statement::VarDecl {
-            var_type: $crate::module::block::VarType::Let,
-            name: stringify!($name).to_string(),
-            initializer: Some(Box::new($initializer.into())),
-        }
+        $crate::module::block::Statement::var_decl($crate::module::block::VarType::Let, stringify!($name), Some($initializer.into()))
     };
     (let $name:ident) => {
-        $crate::module::block::Statement::VarDecl {
-            var_type: $crate::module::block::VarType::Let,
-            name: stringify!($name).to_string(),
-            initializer: None,
-        }
+        $crate::module::block::Statement::var_decl($crate::module::block::VarType::Let, stringify!($name), None)
     };
 
     // Const variable
     (const $name:ident = $initializer:expr) => {
-        $crate::module::block::Statement::VarDecl {
-            var_type: $crate::module::block::VarType::Const,
-            name: stringify!($name).to_string(),
-            initializer: Some(Box::new($initializer.into())),
-        }
+        $crate::module::block::Statement::var_decl($crate::module::block::VarType::Const, stringify!($name), Some($initializer.into()))
     };
 
     // Var variable
     (var $name:ident = $initializer:expr) => {
-        $crate::module::block::Statement::VarDecl {
-            var_type: $crate::module::block::VarType::Var,
-            name: stringify!($name).to_string(),
-            initializer: Some(Box::new($initializer.into())),
-        }
+        $crate::module::block::Statement::var_decl($crate::module::block::VarType::Var, stringify!($name), Some($initializer.into()))
     };
     (var $name:ident) => {
-        $crate::module::block::Statement::VarDecl {
-            var_type: $crate::module::block::VarType::Var,
-            name: stringify!($name).to_string(),
-            initializer: None,
-        }
+        $crate::module::block::Statement::var_decl($crate::module::block::VarType::Var, stringify!($name), None)
+    };
+}
+
+/// Create a module using js module syntax, imports are followed by statements terminated with `;`
+/// (eq. module!("foo" { import { bar } from "bar"; let x = 42; bar(); })). Modules without a name are called `index`.
+#[macro_export]
+macro_rules! module {
+    (@build $builder:expr; ) => {
+        $builder.build()
+    };
+    (@build $builder:expr; import { $($import:ident),+ } from $path:literal; $($rest:tt)*) => {
+        $crate::module!(@build $builder.dependency($crate::import!({ $($import),+ } from $path)); $($rest)*)
+    };
+    (@build $builder:expr; import $path:literal; $($rest:tt)*) => {
+        $crate::module!(@build $builder.dependency($crate::import!($path)); $($rest)*)
+    };
+    (@build $builder:expr; import * as $alias:ident from $path:literal; $($rest:tt)*) => {
+        $crate::module!(@build $builder.dependency($crate::import!(* as $alias from $path)); $($rest)*)
+    };
+    (@build $builder:expr; import $default:ident, { $($import:ident),+ } from $path:literal; $($rest:tt)*) => {
+        $crate::module!(@build $builder.dependency($crate::import!($default, { $($import),+ } from $path)); $($rest)*)
+    };
+    (@build $builder:expr; import $default:ident from $path:literal; $($rest:tt)*) => {
+        $crate::module!(@build $builder.dependency($crate::import!($default from $path)); $($rest)*)
+    };
+    (@build $builder:expr; let $name:ident = $initializer:expr; $($rest:tt)*) => {
+        $crate::module!(@build $builder.statements(vec![$crate::var!(let $name = $initializer)]); $($rest)*)
+    };
+    (@build $builder:expr; let $name:ident; $($rest:tt)*) => {
+        $crate::module!(@build $builder.statements(vec![$crate::var!(let $name)]); $($rest)*)
+    };
+    (@build $builder:expr; const $name:ident = $initializer:expr; $($rest:tt)*) => {
+        $crate::module!(@build $builder.statements(vec![$crate::var!(const $name = $initializer)]); $($rest)*)
+    };
+    (@build $builder:expr; var $name:ident = $initializer:expr; $($rest:tt)*) => {
+        $crate::module!(@build $builder.statements(vec![$crate::var!(var $name = $initializer)]); $($rest)*)
+    };
+    (@build $builder:expr; var $name:ident; $($rest:tt)*) => {
+        $crate::module!(@build $builder.statements(vec![$crate::var!(var $name)]); $($rest)*)
+    };
+    (@build $builder:expr; $statement:expr; $($rest:tt)*) => {
+        $crate::module!(@build $builder.statements(vec![$statement.into()]); $($rest)*)
+    };
+    ($name:literal { $($body:tt)* }) => {
+        $crate::module!(@build $crate::module::Module::builder($name); $($body)*)
+    };
+    ($($body:tt)*) => {
+        $crate::module!(@build $crate::module::Module::builder("index"); $($body)*)
+    };
+}
+
+/// Create new block from statements separated by commas or semicolons (eq. block![var!(let x = 1), assign!(x = 2)]).
+/// The indent defaults to 0 and can be set with block!(indent: 2; [...]).
+#[macro_export]
+macro_rules! block {
+    (indent: $indent:expr; [ $($statement:expr),* $(,)? ]) => {
+        $crate::module::block::Block::builder($indent).stmts(vec![$($statement.into()),*]).build()
+    };
+    (indent: $indent:expr; [ $($statement:expr);* $(;)? ]) => {
+        $crate::module::block::Block::builder($indent).stmts(vec![$($statement.into()),*]).build()
+    };
+    ($($statement:expr),* $(,)?) => {
+        $crate::block!(indent: 0; [$($statement),*])
+    };
+    ($($statement:expr);* $(;)?) => {
+        $crate::block!(indent: 0; [$($statement),*])
     };
 }
 
@@ -127,11 +182,463 @@ macro_rules! binary {
             right: Box::new($right.into()),
         }
     };
+    (&& $left:expr, $right:expr) => {
+        $crate::module::block::Statement::Binary {
+            left: Box::new($left.into()),
+            operator: "&&".to_string(),
+            right: Box::new($right.into()),
+        }
+    };
+    (|| $left:expr, $right:expr) => {
+        $crate::module::block::Statement::Binary {
+            left: Box::new($left.into()),
+            operator: "||".to_string(),
+            right: Box::new($right.into()),
+        }
+    };
+    (?? $left:expr, $right:expr) => {
+        $crate::module::block::Statement::Binary {
+            left: Box::new($left.into()),
+            operator: "??".to_string(),
+            right: Box::new($right.into()),
+        }
+    };
+}
+
+/// Create new instanceof expression (eq. instanceof!(x, Date) is `x instanceof Date`)
+#[macro_export]
+macro_rules! instanceof {
+    ($left:expr, $right:expr) => {
+        $crate::module::block::Statement::Instanceof {
+            left: Box::new($left.into()),
+            right: Box::new($right.into()),
+        }
+    };
+}
+
+/// Create new in expression (eq. in_expr!("key", obj) is `'key' in obj`, `in` is reserved in Rust, hence the name)
+#[macro_export]
+macro_rules! in_expr {
+    ($left:expr, $right:expr) => {
+        $crate::module::block::Statement::InExpr {
+            left: Box::new($left.into()),
+            right: Box::new($right.into()),
+        }
+    };
+}
+
+/// Create new assignment expression (eq. foo = 42, foo ??= 42)
+#[macro_export]
+macro_rules! assign {
+    ($name:ident = $value:expr) => {
+        $crate::module::block::Statement::Assign {
+            name: stringify!($name).to_string(),
+            operator: "=".to_string(),
+            value: Box::new($value.into()),
+        }
+    };
+    ($name:ident += $value:expr) => {
+        $crate::module::block::Statement::Assign {
+            name: stringify!($name).to_string(),
+            operator: "+=".to_string(),
+            value: Box::new($value.into()),
+        }
+    };
+    ($name:ident -= $value:expr) => {
+        $crate::module::block::Statement::Assign {
+            name: stringify!($name).to_string(),
+            operator: "-=".to_string(),
+            value: Box::new($value.into()),
+        }
+    };
+    ($name:ident &&= $value:expr) => {
+        $crate::module::block::Statement::Assign {
+            name: stringify!($name).to_string(),
+            operator: "&&=".to_string(),
+            value: Box::new($value.into()),
+        }
+    };
+    ($name:ident ||= $value:expr) => {
+        $crate::module::block::Statement::Assign {
+            name: stringify!($name).to_string(),
+            operator: "||=".to_string(),
+            value: Box::new($value.into()),
+        }
+    };
+    ($name:ident ??= $value:expr) => {
+        $crate::module::block::Statement::Assign {
+            name: stringify!($name).to_string(),
+            operator: "??=".to_string(),
+            value: Box::new($value.into()),
+        }
+    };
+}
+
+/// Create new identifier referencing a binding by name (eq. ident!(foo) or ident!("foo"))
+#[macro_export]
+macro_rules! ident {
+    ($name:ident) => {
+        $crate::module::block::Statement::Identifier(stringify!($name).to_string())
+    };
+    ($name:expr) => {
+        $crate::module::block::Statement::Identifier($name.to_string())
+    };
+}
+
+/// Create new computed property access (eq. index!(arr[0]), index!(obj[key]) or index!(index!(matrix[i])[j])).
+/// Bare names are identifiers, other objects can be macro calls or parenthesized expressions.
+#[macro_export]
+macro_rules! index {
+    (@key $key:ident) => {
+        $crate::ident!($key)
+    };
+    (@key $key:expr) => {
+        $key.into()
+    };
+    (@build $object:expr; $($key:tt)+) => {
+        $crate::module::block::Statement::Index {
+            object: Box::new($object.into()),
+            index: Box::new($crate::index!(@key $($key)+)),
+        }
+    };
+    ($object:ident [ $($key:tt)+ ]) => {
+        $crate::index!(@build $crate::ident!($object); $($key)+)
+    };
+    ($macro:ident ! ( $($args:tt)* ) [ $($key:tt)+ ]) => {
+        $crate::index!(@build $macro!($($args)*); $($key)+)
+    };
+    (( $object:expr ) [ $($key:tt)+ ]) => {
+        $crate::index!(@build $object; $($key)+)
+    };
+}
+
+/// Create new method call chain (eq. chain!(arr => filter(pred) => map(mapper)) is `arr.filter(pred).map(mapper)`).
+/// Steps without parentheses are property accesses. Bare names are identifiers, other arguments are converted with `into()`,
+/// the object can be a bare name, a macro call or a parenthesized expression.
+#[macro_export]
+macro_rules! chain {
+    (@args [$($done:expr),*]) => {
+        vec![$($done),*]
+    };
+    (@args [$($done:expr),*] $arg:ident $(, $($rest:tt)*)?) => {
+        $crate::chain!(@args [$($done,)* $crate::ident!($arg)] $($($rest)*)?)
+    };
+    (@args [$($done:expr),*] $arg:expr $(, $($rest:tt)*)?) => {
+        $crate::chain!(@args [$($done,)* $arg.into()] $($($rest)*)?)
+    };
+    (@steps $object:expr; ) => {
+        $object
+    };
+    (@steps $object:expr; => $method:ident ( $($args:tt)* ) $($rest:tt)*) => {
+        $crate::chain!(@steps $object.method_call(stringify!($method), $crate::chain!(@args [] $($args)*)); $($rest)*)
+    };
+    (@steps $object:expr; => $property:ident $($rest:tt)*) => {
+        $crate::chain!(@steps $object.member(stringify!($property)); $($rest)*)
+    };
+    ($macro:ident ! ( $($args:tt)* ) $($steps:tt)*) => {
+        $crate::chain!(@steps $macro!($($args)*); $($steps)*)
+    };
+    ($object:ident $($steps:tt)*) => {
+        $crate::chain!(@steps $crate::ident!($object); $($steps)*)
+    };
+    (( $object:expr ) $($steps:tt)*) => {
+        $crate::chain!(@steps $crate::module::block::Statement::from($object); $($steps)*)
+    };
+}
+
+/// Create new template literal from text parts and expressions, optionally separated by commas
+/// (eq. template!("Hello ", name, "!") is `` `Hello ${name}!` ``). Bare names are identifiers,
+/// other expressions can be macro calls or parenthesized expressions.
+#[macro_export]
+macro_rules! template {
+    (@parts $quasis:ident, $expressions:ident; ) => {};
+    (@parts $quasis:ident, $expressions:ident; , $($rest:tt)*) => {
+        $crate::template!(@parts $quasis, $expressions; $($rest)*)
+    };
+    (@parts $quasis:ident, $expressions:ident; $text:literal $($rest:tt)*) => {
+        $quasis.last_mut().unwrap().push_str($text);
+        $crate::template!(@parts $quasis, $expressions; $($rest)*)
+    };
+    (@parts $quasis:ident, $expressions:ident; $macro:ident ! ( $($args:tt)* ) $($rest:tt)*) => {
+        $crate::template!(@expression $quasis, $expressions; $macro!($($args)*));
+        $crate::template!(@parts $quasis, $expressions; $($rest)*)
+    };
+    (@parts $quasis:ident, $expressions:ident; $name:ident $($rest:tt)*) => {
+        $crate::template!(@expression $quasis, $expressions; $crate::ident!($name));
+        $crate::template!(@parts $quasis, $expressions; $($rest)*)
+    };
+    (@parts $quasis:ident, $expressions:ident; ( $expression:expr ) $($rest:tt)*) => {
+        $crate::template!(@expression $quasis, $expressions; $crate::module::block::Statement::from($expression));
+        $crate::template!(@parts $quasis, $expressions; $($rest)*)
+    };
+    (@expression $quasis:ident, $expressions:ident; $expression:expr) => {
+        $expressions.push($expression);
+        $quasis.push(String::new());
+    };
+    () => {
+        $crate::module::block::Statement::TemplateLiteral { quasis: vec![String::new()], expressions: Vec::new() }
+    };
+    ($($parts:tt)+) => {{
+        let mut quasis = vec![String::new()];
+        let mut expressions: Vec<$crate::module::block::Statement> = Vec::new();
+        $crate::template!(@parts quasis, expressions; $($parts)+);
+        $crate::module::block::Statement::TemplateLiteral { quasis, expressions }
+    }};
+}
+
+/// Create new tagged template (eq. tagged_template!(html; template!("<p>", content, "</p>")) is ``html`<p>${content}</p>` ``)
+#[macro_export]
+macro_rules! tagged_template {
+    ($tag:ident; $template:expr) => {
+        $crate::tagged_template!(($crate::ident!($tag)); $template)
+    };
+    (( $tag:expr ); $template:expr) => {
+        $crate::module::block::Statement::TaggedTemplate {
+            tag: Box::new($tag.into()),
+            template: Box::new($template),
+        }
+    };
+}
+
+/// Create new await expression (`await` is reserved in Rust, hence the name)
+#[macro_export]
+macro_rules! await_expr {
+    ($value:expr) => {
+        $crate::module::block::Statement::Await(Box::new($value.into()))
+    };
+}
+
+/// Create new typeof expression (`typeof` is reserved in Rust, hence the name)
+#[macro_export]
+macro_rules! type_of {
+    ($value:expr) => {
+        $crate::module::block::Statement::TypeOf(Box::new($value.into()))
+    };
+}
+
+/// Create new void expression (eq. void_expr!(0) is `void 0`)
+#[macro_export]
+macro_rules! void_expr {
+    ($value:expr) => {
+        $crate::module::block::Statement::VoidExpr(Box::new($value.into()))
+    };
+}
+
+/// Create new yield expression (`yield` is reserved in Rust, hence the name)
+#[macro_export]
+macro_rules! yield_expr {
+    () => {
+        $crate::module::block::Statement::Yield {
+            value: None,
+            delegate: false,
+        }
+    };
+    ($value:expr) => {
+        $crate::module::block::Statement::Yield {
+            value: Some(Box::new($value.into())),
+            delegate: false,
+        }
+    };
+}
+
+/// Create new delegating yield expression (eq. yield* gen())
+#[macro_export]
+macro_rules! yield_delegate {
+    ($value:expr) => {
+        $crate::module::block::Statement::Yield {
+            value: Some(Box::new($value.into())),
+            delegate: true,
+        }
+    };
+}
+
+/// Create new expression statement (eq. expr_stmt!(assign!(x = 5)) is `x = 5;`)
+#[macro_export]
+macro_rules! expr_stmt {
+    ($expr:expr) => {
+        $crate::module::block::Statement::ExpressionStatement(Box::new($expr.into()))
+    };
+}
+
+/// Create new call with TypeScript type arguments (eq. call_with_type_args!(identity, [string], ["hello"])).
+/// Arguments are converted like in `chain!`, a callee other than an identifier must be in parentheses.
+#[cfg(feature = "typescript")]
+#[macro_export]
+macro_rules! call_with_type_args {
+    ($callee:ident, [$($type_arg:ident),* $(,)?], [$($args:tt)*]) => {
+        $crate::call_with_type_args!(($crate::ident!($callee)), [$($type_arg),*], [$($args)*])
+    };
+    (($callee:expr), [$($type_arg:ident),* $(,)?], [$($args:tt)*]) => {
+        $crate::module::block::Statement::from($callee).call_with_type_args(
+            vec![$($crate::module::typescript::TsType::ident(stringify!($type_arg))),*],
+            $crate::chain!(@args [] $($args)*)
+        )
+    };
+}
+
+/// Create new TypeScript `as const` assertion
+#[cfg(feature = "typescript")]
+#[macro_export]
+macro_rules! as_const {
+    ($value:expr) => {
+        $crate::module::block::Statement::TsAsConst(Box::new($value.into()))
+    };
+}
+
+/// Create new class declaration using js class syntax. Members are separated by commas and can be
+/// constructors, methods (optionally `static`, `async`, `*`, `get` or `set`), fields, private `#` members
+/// or `ClassMember` expressions. Method bodies are comma-separated statements
+/// (eq. class!(Foo extends Bar { constructor(x) { Statement::Raw("super(x)".to_string()) }, static count = 0, #secret = 42 })).
+#[macro_export]
+macro_rules! class {
+    // Members are collected one by one, modifiers are stored as (static async generator).
+    (@members [$($member:expr),*]) => {
+        vec![$($member),*]
+    };
+    (@members [$($member:expr),*] static $($rest:tt)+) => {
+        $crate::class!(@member [$($member),*] (true false false) $($rest)+)
+    };
+    (@members [$($member:expr),*] $($rest:tt)+) => {
+        $crate::class!(@member [$($member),*] (false false false) $($rest)+)
+    };
+    (@member [$($member:expr),*] ($static:tt false false) async $($rest:tt)+) => {
+        $crate::class!(@member [$($member),*] ($static true false) $($rest)+)
+    };
+    (@member [$($member:expr),*] ($static:tt $async:tt false) * $($rest:tt)+) => {
+        $crate::class!(@member [$($member),*] ($static $async true) $($rest)+)
+    };
+    (@member [$($member:expr),*] (false false false) constructor ($($param:ident),* $(,)?) { $($body:tt)* } $(, $($rest:tt)*)?) => {
+        $crate::class!(@members [$($member,)* $crate::class!(@method (false false false) false Constructor constructor ($($param),*) { $($body)* })] $($($rest)*)?)
+    };
+    (@member [$($member:expr),*] ($static:tt false false) get $name:ident () { $($body:tt)* } $(, $($rest:tt)*)?) => {
+        $crate::class!(@members [$($member,)* $crate::class!(@method ($static false false) false Get $name () { $($body)* })] $($($rest)*)?)
+    };
+    (@member [$($member:expr),*] ($static:tt false false) set $name:ident ($param:ident) { $($body:tt)* } $(, $($rest:tt)*)?) => {
+        $crate::class!(@members [$($member,)* $crate::class!(@method ($static false false) false Set $name ($param) { $($body)* })] $($($rest)*)?)
+    };
+    (@member [$($member:expr),*] $modifiers:tt # $name:ident ($($param:ident),* $(,)?) { $($body:tt)* } $(, $($rest:tt)*)?) => {
+        $crate::class!(@members [$($member,)* $crate::class!(@method $modifiers true Method $name ($($param),*) { $($body)* })] $($($rest)*)?)
+    };
+    (@member [$($member:expr),*] $modifiers:tt $name:ident ($($param:ident),* $(,)?) { $($body:tt)* } $(, $($rest:tt)*)?) => {
+        $crate::class!(@members [$($member,)* $crate::class!(@method $modifiers false Method $name ($($param),*) { $($body)* })] $($($rest)*)?)
+    };
+    (@member [$($member:expr),*] ($static:tt false false) # $name:ident $(= $value:expr)? $(, $($rest:tt)*)?) => {
+        $crate::class!(@members [$($member,)* $crate::class!(@field $static true $name $(= $value)?)] $($($rest)*)?)
+    };
+    (@member [$($member:expr),*] ($static:tt false false) $name:ident $(= $value:expr)? $(, $($rest:tt)*)?) => {
+        $crate::class!(@members [$($member,)* $crate::class!(@field $static false $name $(= $value)?)] $($($rest)*)?)
+    };
+    (@member [$($member:expr),*] (false false false) $value:expr $(, $($rest:tt)*)?) => {
+        $crate::class!(@members [$($member,)* $value] $($($rest)*)?)
+    };
+    (@method ($static:tt $async:tt $generator:tt) $private:tt $kind:ident $name:ident ($($param:ident),*) { $($body:tt)* }) => {
+        $crate::module::class::ClassMember::Method {
+            is_static: $static,
+            is_async: $async,
+            is_generator: $generator,
+            is_private: $private,
+            kind: $crate::module::class::MethodKind::$kind,
+            name: stringify!($name).to_string(),
+            params: vec![$($crate::module::class::FunctionParam::new(stringify!($param))),*],
+            body: Box::new($crate::block![$($body)*]),
+        }
+    };
+    (@field $static:tt $private:tt $name:ident = $value:expr) => {
+        $crate::module::class::ClassMember::Field {
+            is_static: $static,
+            is_private: $private,
+            name: stringify!($name).to_string(),
+            value: Some(Box::new($value.into())),
+        }
+    };
+    (@field $static:tt $private:tt $name:ident) => {
+        $crate::module::class::ClassMember::Field {
+            is_static: $static,
+            is_private: $private,
+            name: stringify!($name).to_string(),
+            value: None,
+        }
+    };
+
+    ($name:ident extends $superclass:ident { $($members:tt)* }) => {
+        $crate::module::block::Statement::ClassDecl {
+            name: stringify!($name).to_string(),
+            superclass: Some(Box::new($crate::ident!($superclass))),
+            body: $crate::class!(@members [] $($members)*),
+        }
+    };
+    ($name:ident extends $superclass:ident) => {
+        $crate::class!($name extends $superclass {})
+    };
+    ($name:ident { $($members:tt)* }) => {
+        $crate::module::block::Statement::ClassDecl {
+            name: stringify!($name).to_string(),
+            superclass: None,
+            body: $crate::class!(@members [] $($members)*),
+        }
+    };
+    ($name:ident) => {
+        $crate::class!($name {})
+    };
+}
+
+/// Create new regular expression literal (eq. regex!("[a-z]+", "gi"))
+#[macro_export]
+macro_rules! regex {
+    ($pattern:expr, $flags:expr) => {
+        $crate::module::block::Statement::RegexLiteral {
+            pattern: $pattern.to_string(),
+            flags: $flags.to_string(),
+        }
+    };
+    ($pattern:expr) => {
+        $crate::regex!($pattern, "")
+    };
+}
+
+/// Create new BigInt literal, keeping the number's notation (eq. bigint!(0xFF) is 0xFFn)
+#[macro_export]
+macro_rules! bigint {
+    ($value:literal) => {
+        $crate::module::block::Statement::BigIntLiteral(stringify!($value).to_string())
+    };
+}
+
+/// Create new JSDoc comment builder. Tags are separated with semicolons
+/// (eq. jsdoc!("Add numbers."; @param a: "number" => "First number"; @returns "number" => "The sum"))
+#[macro_export]
+macro_rules! jsdoc {
+    (@tags $doc:expr; ) => {
+        $doc
+    };
+    (@tags $doc:expr; @param $name:ident : $type:literal $(=> $description:literal)? $(; $($rest:tt)*)?) => {
+        $crate::jsdoc!(@tags $doc.param(stringify!($name), $type, concat!($($description)?)); $($($rest)*)?)
+    };
+    (@tags $doc:expr; @returns $type:literal $(=> $description:literal)? $(; $($rest:tt)*)?) => {
+        $crate::jsdoc!(@tags $doc.returns($type, concat!($($description)?)); $($($rest)*)?)
+    };
+    (@tags $doc:expr; @throws $type:literal $(=> $description:literal)? $(; $($rest:tt)*)?) => {
+        $crate::jsdoc!(@tags $doc.throws($type, concat!($($description)?)); $($($rest)*)?)
+    };
+    (@tags $doc:expr; @template $name:ident $(; $($rest:tt)*)?) => {
+        $crate::jsdoc!(@tags $doc.template(stringify!($name)); $($($rest)*)?)
+    };
+    (@tags $doc:expr; @see $link:literal $(; $($rest:tt)*)?) => {
+        $crate::jsdoc!(@tags $doc.see($link); $($($rest)*)?)
+    };
+    ($description:literal $(; $($rest:tt)*)?) => {
+        $crate::jsdoc!(@tags $crate::codegen::jsdoc::JsDoc::new().description($description); $($($rest)*)?)
+    };
+    ($($rest:tt)*) => {
+        $crate::jsdoc!(@tags $crate::codegen::jsdoc::JsDoc::new(); $($rest)*)
+    };
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::module::Dependency;
+    use crate::codegen::CodegenError;
+    use crate::module::{Dependency, Module};
+    use crate::module::block::{Block, Statement, VarType};
 
     #[test]
     fn test_import_macro() {
@@ -142,7 +649,19 @@ mod tests {
         );
 
         let import = import!(foo from "bar");
-        assert_eq!(import, Dependency::new(vec!["foo".to_string()], "bar"));
+        assert_eq!(import, Dependency::default("foo", "bar"));
+
+        let import = import!(React, { useState, useEffect } from "react");
+        assert_eq!(
+            import,
+            Dependency::default_with_named("React", vec!["useState".to_string(), "useEffect".to_string()], "react")
+        );
+
+        let import = import!(* as lodash from "lodash");
+        assert_eq!(import, Dependency::namespace("lodash", "lodash"));
+
+        let import = import!("polyfill");
+        assert_eq!(import, Dependency::side_effect("polyfill"));
     }
 
     #[test]
@@ -150,51 +669,31 @@ mod tests {
         let var = var!(let foo = 42);
         assert_eq!(
             var,
-            crate::module::block::Statement::VarDecl {
-                var_type: crate::module::block::VarType::Let,
-                name: "foo".to_string(),
-                initializer: Some(Box::new(42.into())),
-            }
+            crate::module::block::Statement::var_decl(crate::module::block::VarType::Let, "foo", Some(42.into()))
         );
 
         let var = var!(let foo);
         assert_eq!(
             var,
-            crate::module::block::Statement::VarDecl {
-                var_type: crate::module::block::VarType::Let,
-                name: "foo".to_string(),
-                initializer: None,
-            }
+            crate::module::block::Statement::var_decl(crate::module::block::VarType::Let, "foo", None)
         );
 
         let var = var!(const foo = 42);
         assert_eq!(
             var,
-            crate::module::block::Statement::VarDecl {
-                var_type: crate::module::block::VarType::Const,
-                name: "foo".to_string(),
-                initializer: Some(Box::new(42.into())),
-            }
+            crate::module::block::Statement::var_decl(crate::module::block::VarType::Const, "foo", Some(42.into()))
         );
 
         let var = var!(var foo = 42);
         assert_eq!(
             var,
-            crate::module::block::Statement::VarDecl {
-                var_type: crate::module::block::VarType::Var,
-                name: "foo".to_string(),
-                initializer: Some(Box::new(42.into())),
-            }
+            crate::module::block::Statement::var_decl(crate::module::block::VarType::Var, "foo", Some(42.into()))
         );
 
         let var = var!(var foo);
         assert_eq!(
             var,
-            crate::module::block::Statement::VarDecl {
-                var_type: crate::module::block::VarType::Var,
-                name: "foo".to_string(),
-                initializer: None,
-            }
+            crate::module::block::Statement::var_decl(crate::module::block::VarType::Var, "foo", None)
         );
     }
 
@@ -210,4 +709,333 @@ mod tests {
             }
         );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_nullish_coalescing_macro() {
+        let a = ident!(a);
+        let b = ident!(b);
+        let c = ident!(c);
+
+        let binary = binary!(?? a, binary!(|| b, c));
+        assert_eq!(binary.generate().unwrap(), "a ?? (b || c)");
+        assert_ne!(binary.generate().unwrap(), "a ?? b || c");
+    }
+
+    #[test]
+    fn test_instanceof_and_in_macros() {
+        let x = ident!(x);
+        let date = ident!(Date);
+        assert_eq!(instanceof!(x, date).generate().unwrap(), "x instanceof Date");
+        assert_eq!(in_expr!("key", ident!(obj)).generate().unwrap(), "'key' in obj");
+        assert_eq!(binary!(!== in_expr!("a", ident!(b)), false).generate().unwrap(), "'a' in b !== false");
+    }
+
+    #[test]
+    fn test_type_of_and_void_macros() {
+        assert_eq!(type_of!(ident!(undeclaredVar)).generate().unwrap(), "typeof undeclaredVar");
+        assert_eq!(void_expr!(0).generate().unwrap(), "void 0");
+        assert_eq!(void_expr!(0), Statement::VoidExpr(Box::new(0.into())));
+    }
+
+    #[test]
+    fn test_ident_macro() {
+        assert_eq!(ident!(foo), Statement::Identifier("foo".to_string()));
+        assert_eq!(ident!("foo"), ident!(foo));
+        let name = String::from("bar");
+        assert_eq!(ident!(name.as_str()).generate().unwrap(), "bar");
+        assert!(ident!("not valid").generate().is_err());
+    }
+
+    #[test]
+    fn test_index_macro() {
+        assert_eq!(index!(arr[0]).generate().unwrap(), "arr[0]");
+        assert_eq!(index!(obj["key"]).generate().unwrap(), "obj['key']");
+        assert_eq!(index!(index!(matrix[i])[j]).generate().unwrap(), "matrix[i][j]");
+        assert_eq!(index!((binary!(?? ident!(a), ident!(b)))[0]).generate().unwrap(), "(a ?? b)[0]");
+        assert_eq!(index!(arr[0]), Statement::Index { object: Box::new(ident!(arr)), index: Box::new(0.into()) });
+    }
+
+    #[test]
+    fn test_chain_macro() {
+        let chain = chain!(arr => filter(pred) => map(mapper) => join(sep));
+        assert_eq!(chain.generate().unwrap(), "arr.filter(pred).map(mapper).join(sep)");
+        assert_eq!(chain, ident!(arr)
+            .method_call("filter", vec![ident!(pred)])
+            .method_call("map", vec![ident!(mapper)])
+            .method_call("join", vec![ident!(sep)]));
+
+        assert_eq!(chain!(items => length).generate().unwrap(), "items.length");
+        assert_eq!(chain!(index!(rows[0]) => slice(1, "x", binary!(+ 1, 2))).generate().unwrap(), "rows[0].slice(1, 'x', 1 + 2)");
+        assert_eq!(chain!((5) => toString()).generate().unwrap(), "(5).toString()");
+    }
+
+    #[test]
+    fn test_template_macros() {
+        let template = template!("Hello ", name);
+        assert_eq!(template, Statement::TemplateLiteral {
+            quasis: vec!["Hello ".to_string(), String::new()],
+            expressions: vec![ident!(name)]
+        });
+        assert_eq!(template.generate().unwrap(), "`Hello ${name}`");
+        assert_eq!(template!().generate().unwrap(), "``");
+        assert_eq!(template!(a b "-" (1) binary!(+ 1, 2)).generate().unwrap(), "`${a}${b}-${1}${1 + 2}`");
+
+        let css = tagged_template!(css; template!(".class { color: " color " }"));
+        assert_eq!(css.generate().unwrap(), "css`.class { color: ${color} }`");
+        let html = tagged_template!((chain!(lit => html)); template!("<p>", content, "</p>"));
+        assert_eq!(html.generate().unwrap(), "lit.html`<p>${content}</p>`");
+    }
+
+    #[test]
+    fn test_assign_macro() {
+        let assign = assign!(foo = 42);
+        assert_eq!(
+            assign,
+            Statement::Assign {
+                name: "foo".to_string(),
+                operator: "=".to_string(),
+                value: Box::new(42.into()),
+            }
+        );
+
+        let assign = assign!(foo ??= 42);
+        assert_eq!(assign.generate().unwrap(), "foo ??= 42");
+    }
+
+    #[test]
+    fn test_await_macro() {
+        let fetch = Statement::Raw("fetch(url)".to_string());
+        assert_eq!(await_expr!(fetch.clone()).generate().unwrap(), "await fetch(url)");
+
+        let assign = assign!(response = await_expr!(fetch));
+        assert_eq!(assign.generate().unwrap(), "response = await fetch(url)");
+    }
+
+    #[test]
+    fn test_yield_macros() {
+        assert_eq!(yield_expr!().generate().unwrap(), "yield");
+        assert_eq!(yield_expr!(42).generate().unwrap(), "yield 42");
+        assert_eq!(
+            yield_delegate!(Statement::Raw("gen()".to_string())).generate().unwrap(),
+            "yield* gen()"
+        );
+    }
+
+    #[test]
+    fn test_regex_macro() {
+        assert_eq!(regex!("a/b", "g").generate().unwrap(), "/a\\/b/g");
+        assert_eq!(regex!("^foo$").generate().unwrap(), "/^foo$/");
+    }
+
+    #[test]
+    fn test_bigint_macro() {
+        assert_eq!(bigint!(9007199254740993).generate().unwrap(), "9007199254740993n");
+        assert_eq!(bigint!(0xFF).generate().unwrap(), "0xFFn");
+    }
+
+    #[test]
+    fn test_class_macro() {
+        use crate::module::class::ClassMember;
+
+        let class = class!(Foo extends Bar {
+            ClassMember::field("x", Some(1.into())),
+        });
+        assert_eq!(class.generate().unwrap(), "class Foo extends Bar {\n    x = 1;\n}");
+        assert_eq!(class!(Foo).generate().unwrap(), "class Foo {}");
+
+        let class = class!(Counter extends Base {
+            constructor(x) {
+                Statement::Raw("super()".to_string()),
+                Statement::Raw("this.x = x".to_string()),
+            },
+            get value() { Statement::Raw("return this.x".to_string()) },
+            set value(x) { Statement::Raw("this.x = x".to_string()) },
+            static count = 0,
+            static create() {},
+            async *items() {},
+            #secret = 42,
+            #reveal() { Statement::Raw("return this.#secret".to_string()) },
+            ready,
+            ClassMember::field("x", Some(1.into())),
+        });
+        assert_eq!(
+            class.generate().unwrap(),
+            concat!(
+                "class Counter extends Base {\n",
+                "    constructor(x) {\n",
+                "        super()\n",
+                "        this.x = x\n",
+                "    }\n",
+                "    get value() {\n",
+                "        return this.x\n",
+                "    }\n",
+                "    set value(x) {\n",
+                "        this.x = x\n",
+                "    }\n",
+                "    static count = 0;\n",
+                "    static create() {}\n",
+                "    async *items() {}\n",
+                "    #secret = 42;\n",
+                "    #reveal() {\n",
+                "        return this.#secret\n",
+                "    }\n",
+                "    ready;\n",
+                "    x = 1;\n",
+                "}"
+            )
+        );
+        assert_eq!(class!(Foo { #secret }), class!(Foo { ClassMember::private_field("secret", None) }));
+    }
+
+    #[test]
+    #[cfg(feature = "typescript")]
+    fn test_call_with_type_args_macro() {
+        assert_eq!(call_with_type_args!(identity, [string], ["hello"]).generate().unwrap(), "identity<string>('hello')");
+        assert_eq!(
+            call_with_type_args!((ident!(api).member("get")), [User, Error], [url, 1]).generate().unwrap(),
+            "api.get<User, Error>(url, 1)"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "typescript")]
+    fn test_as_const_macro() {
+        let object = Statement::Raw("{ foo: 1 }".to_string());
+        assert_eq!(as_const!(object).generate().unwrap(), "({ foo: 1 } as const)");
+    }
+
+    #[test]
+    fn test_jsdoc_macro() {
+        let doc = jsdoc!(
+            "Add two numbers.";
+            @template T;
+            @param a: "number" => "First number";
+            @param b: "number";
+            @returns "number" => "The sum";
+            @throws "RangeError";
+            @see "https://example.com";
+        );
+        assert_eq!(
+            doc.render(),
+            concat!(
+                "/**\n",
+                " * Add two numbers.\n",
+                " *\n",
+                " * @template T\n",
+                " * @param {number} a First number\n",
+                " * @param {number} b\n",
+                " * @returns {number} The sum\n",
+                " * @throws {RangeError}\n",
+                " * @see https://example.com\n",
+                " */"
+            )
+        );
+        assert_eq!(jsdoc!(@returns "void").render(), "/**\n * @returns {void}\n */");
+    }
+
+    #[test]
+    fn test_expr_stmt_macro() {
+        let statement = expr_stmt!(assign!(x = 5));
+        assert_eq!(statement, Statement::ExpressionStatement(Box::new(assign!(x = 5))));
+        assert_eq!(statement.generate().unwrap(), "x = 5;");
+        assert_eq!(expr_stmt!(await_expr!(ident!(ready))).generate().unwrap(), "await ready;");
+    }
+
+    #[test]
+    fn test_module_macro() {
+        let module = module!("mymod" {
+            import { foo, bar } from "bar";
+            import React from "react";
+            import "polyfill";
+            let x = 42;
+            const y = "hello";
+            var z;
+            Statement::Raw("foo(x)".to_string());
+        });
+
+        let expected = Module::builder("mymod")
+            .dependency(Dependency::new(vec!["foo".to_string(), "bar".to_string()], "bar"))
+            .dependency(Dependency::default("React", "react"))
+            .dependency(Dependency::side_effect("polyfill"))
+            .statement(Statement::var_decl(VarType::Let, "x", Some(42.into()))).unwrap()
+            .statement(Statement::var_decl(VarType::Const, "y", Some("hello".into()))).unwrap()
+            .statement(Statement::var_decl(VarType::Var, "z", None)).unwrap()
+            .statement(Statement::Raw("foo(x)".to_string())).unwrap()
+            .build();
+        assert_eq!(module, expected);
+        assert_eq!(
+            module.to_js_string().unwrap(),
+            concat!(
+                "import { foo, bar } from 'bar';\n",
+                "import React from 'react';\n",
+                "import 'polyfill';\n",
+                "let x = 42;\n",
+                "const y = 'hello';\n",
+                "var z;\n",
+                "foo(x)\n"
+            )
+        );
+
+        let module = module! {
+            import * as path from "path";
+            await_expr!(ident!(ready));
+        };
+        assert_eq!(module.name, "index");
+        assert_eq!(module.to_js_string().unwrap(), "import * as path from 'path';\nawait ready;\n");
+        assert_eq!(module!("empty" {}), Module::create("empty"));
+    }
+
+    #[test]
+    fn test_block_macro() {
+        assert_eq!(block![], Block::new(0));
+        assert_eq!(block!(indent: 2; []), Block::new(2));
+
+        let block = block![
+            var!(let x = 1),
+            assign!(x += 2),
+            Statement::Raw("foo(x)".to_string()),
+            Statement::Block(Box::new(block![var!(const y = true)])),
+            class!(Foo),
+            "done",
+        ];
+        let mut expected = Block::new(0);
+        expected.stmt(var!(let x = 1)).unwrap()
+            .stmt(assign!(x += 2)).unwrap()
+            .raw("foo(x)")
+            .stmt(Statement::Block(Box::new(block![var!(const y = true)]))).unwrap()
+            .stmt(class!(Foo)).unwrap()
+            .stmt("done".into()).unwrap();
+        assert_eq!(block, expected);
+        assert_eq!(
+            block.generate().unwrap(),
+            "let x = 1;\nx += 2;\nfoo(x)\nconst y = true;\nclass Foo {}\n'done';\n"
+        );
+
+        let block = block!(indent: 1; [var!(let x); assign!(x = 1);]);
+        assert_eq!(block, block!(indent: 1; [var!(let x), assign!(x = 1)]));
+        assert_eq!(block.generate().unwrap(), "    let x;\n    x = 1;\n");
+        assert_eq!(block![var!(let x); assign!(x = 1)], block![var!(let x), assign!(x = 1)]);
+
+        assert!(block![await_expr!(ident!(ready))].generate().is_err());
+        assert!(block![Statement::Raw("foo()".to_string()), yield_expr!()].generate().is_err());
+    }
+
+    #[test]
+    fn test_function_contexts() {
+        let class = class!(Loader {
+            async load() { await_expr!(ident!(ready)) },
+            *items() { yield_expr!(1) },
+        });
+        assert_eq!(
+            class.generate().unwrap(),
+            "class Loader {\n    async load() {\n        await ready;\n    }\n    *items() {\n        yield 1;\n    }\n}"
+        );
+        assert!(matches!(class!(Loader { load() { await_expr!(ident!(ready)) } }).generate(), Err(CodegenError::AwaitOutsideAsync)));
+        assert!(matches!(class!(Loader { async items() { yield_expr!(1) } }).generate(), Err(CodegenError::YieldOutsideGenerator)));
+
+        let module = module!("gen" { yield_expr!(1); });
+        assert!(matches!(module.to_js_string(), Err(CodegenError::YieldOutsideGenerator)));
+        let module = Module::builder("gen").statements(vec![yield_expr!(1)]).build();
+        assert!(module.to_js_string().is_err());
+    }
+}
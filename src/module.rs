@@ -1,15 +1,63 @@
-use std::{path::PathBuf, io::Write, ops::{DerefMut, Deref}};
+//! Modules, blocks and statements can all be turned into code:
+//!
+//! - `Module::to_js_string` generates the whole file, including the shebang, prelude and imports,
+//!   using the module's options and format.
+//! - `Block::to_js_string` generates only the block's statements, each on its own line,
+//!   indented to the block's level and terminated according to the options.
+//! - `Statement::to_js_string` generates a single statement without indentation or terminator,
+//!   so it can be used as a part of another statement.
+
+use std::{borrow::Cow, path::{Path, PathBuf}, io::Write, ops::{DerefMut, Deref}};
+
+use crate::codegen::{CodegenError, CodegenOptions};
+use crate::codegen::sourcemap::{end_position, SourceMap};
 
 pub mod block;
+pub mod class;
+pub mod graph;
+#[cfg(feature = "jsx")]
+pub mod jsx;
+pub mod set;
+pub mod transform;
+#[cfg(feature = "typescript")]
+pub mod typescript;
+pub mod validate;
 
 /// Struct that represents a js module (file).
+///
+/// With the `serde` feature, modules and all AST types can be serialized. Structs are objects with the
+/// same field names, enums use serde's default external tagging (eg. `{"StringLiteral":"foo"}`, `"Null"`
+/// or `{"Identifier":"x"}`). The fields of the main block (`indent`, `statements` and `context`)
+/// are inlined into the module object. Fields and variants are only added in minor versions.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Module {
     /// The name of the module (file without extension).
     pub name: String,
     /// The dependencies of the module.
     pub dependencies: Vec<Dependency>,
     /// Main block of the module.
+    #[cfg_attr(feature = "serde", serde(flatten))]
     pub main_block: block::Block,
+    /// Statements emitted before the imports (eg. `// @ts-nocheck`).
+    pub prelude: Vec<block::Statement>,
+    /// Interpreter of an executable script (eg. `/usr/bin/env node`).
+    pub shebang: Option<String>,
+    /// Options used when generating the module's code.
+    pub options: CodegenOptions,
+    /// Module system used for imports and exports.
+    pub format: ModuleFormat,
+}
+
+/// Module system used by a generated module.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ModuleFormat {
+    /// ES modules (`import` and `export`).
+    #[default]
+    Esm,
+    /// CommonJS modules (`require()` and `module.exports`).
+    CommonJs
 }
 
 impl Module {
@@ -18,8 +66,200 @@ impl Module {
         Self {
             name: name.to_string(),
             dependencies: Vec::new(),
-            main_block: block::Block::new(0),
+            // Top-level await is allowed in ES modules.
+            main_block: block::Block::new(0).with_context(block::BlockContext::AsyncFunction),
+            prelude: Vec::new(),
+            shebang: None,
+            options: CodegenOptions::default(),
+            format: ModuleFormat::default(),
+        }
+    }
+
+    /// Create a new builder for a module with the given name.
+    pub fn builder(name: &str) -> ModuleBuilder {
+        ModuleBuilder {
+            module: Self::create(name)
+        }
+    }
+
+    /// Create a new barrel module re-exporting each `(symbol, path)` pair
+    /// (eg. `export { foo } from './foo.js'`).
+    pub fn create_barrel(name: &str, exports: &[(&str, &str)]) -> Self {
+        let mut module = Self::create(name);
+        module.extend_stmts(exports.iter()
+            .map(|(symbol, path)| block::Statement::ExportList {
+                names: vec![block::ExportSpecifier::new(symbol)],
+                from_path: Some(path.to_string())
+            })
+            .collect());
+        module
+    }
+
+    /// Create a new barrel module re-exporting everything from each path
+    /// (eg. `export * from './foo.js'`).
+    pub fn create_barrel_all(name: &str, paths: &[&str]) -> Self {
+        let mut module = Self::create(name);
+        module.extend_stmts(paths.iter().map(|path| block::Statement::ExportAll(path.to_string())).collect());
+        module
+    }
+
+    /// Combine two modules into one, keeping the name, options and shebang of this module
+    /// (the shebang of `other` is used if this module has none).
+    /// Imports of the same path are merged into a single import where possible,
+    /// statements of `other` are appended after the statements of this module.
+    pub fn merge(mut self, other: Module) -> Module {
+        for dependency in other.dependencies {
+            merge_dependency(&mut self.dependencies, dependency);
+        }
+        for statement in other.prelude {
+            if !self.prelude.contains(&statement) {
+                self.prelude.push(statement);
+            }
+        }
+        if self.shebang.is_none() {
+            self.shebang = other.shebang;
         }
+        self.main_block.statements.extend(other.main_block.statements);
+        self
+    }
+
+    /// Find imported names that are not referenced anywhere in the main block.
+    /// This is a textual scan of the generated code of each statement (statements that fail to generate are skipped),
+    /// so a name only counts as used if it appears as a whole identifier.
+    pub fn unused_imports(&self) -> Vec<(&Dependency, &str)> {
+        let code = self.main_block.statements.iter()
+            .filter_map(|statement| statement.generate_with_options(&self.options).ok())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let mut unused = Vec::new();
+        for dependency in &self.dependencies {
+            let names = dependency.default.iter()
+                .chain(dependency.namespace.iter())
+                .chain(dependency.imports.iter());
+            for name in names {
+                // Aliased imports (eg. `foo as bar`) are referenced by their local name.
+                let local = name.rsplit(" as ").next().unwrap_or(name).trim();
+                if !contains_identifier(&code, local) {
+                    unused.push((dependency, name.as_str()));
+                }
+            }
+        }
+        unused
+    }
+
+    /// Create a copy of the module containing only the code reachable from the given exports (eg. `&["foo", "default"]`).
+    /// Exports are kept if their exported name is listed, other statements are kept if they declare a name referenced
+    /// by a kept statement (see `Statement::collect_declarations` and `Statement::collect_identifiers`).
+    /// Statements that declare nothing are kept if they reference such a name (eg. `count += 1` or `init(config)`),
+    /// the names they reference are kept as well. Raw code, re-exports of everything and TypeScript declarations
+    /// are always kept, as the names they use are unknown. Imports are not changed.
+    pub fn tree_shake(&self, entry_exports: &[&str]) -> Module {
+        let statements = &self.main_block.statements;
+        let mut kept: Vec<Option<block::Statement>> = statements.iter()
+            .map(|statement| shake_root(statement, entry_exports))
+            .collect();
+        let mut live: Vec<String> = kept.iter()
+            .flatten()
+            .flat_map(|statement| statement.collect_identifiers())
+            .map(str::to_string)
+            .collect();
+
+        // Keep declarations and uses of live names until no more names are found.
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (statement, kept) in statements.iter().zip(&mut kept) {
+                if kept.is_some() || is_export(statement) {
+                    continue;
+                }
+                let declarations = statement.collect_declarations();
+                let names = if declarations.is_empty() { statement.collect_identifiers() } else { declarations };
+                if names.iter().any(|name| live.iter().any(|live| live == name)) {
+                    live.extend(statement.collect_identifiers().into_iter().map(str::to_string));
+                    *kept = Some(statement.clone());
+                    changed = true;
+                }
+            }
+        }
+
+        let mut module = self.clone();
+        module.main_block.statements = kept.into_iter().flatten().collect();
+        module
+    }
+
+    /// Set the options used when generating the module's code.
+    pub fn set_options(&mut self, options: CodegenOptions) {
+        self.options = options;
+    }
+
+    /// Set the module system used for imports and exports.
+    pub fn set_module_format(&mut self, format: ModuleFormat) {
+        self.format = format;
+    }
+
+    /// Make the module an executable script run by the given interpreter (eg. `/usr/bin/env node`).
+    pub fn add_shebang(&mut self, interpreter: &str) {
+        self.shebang = Some(interpreter.to_string());
+    }
+
+    /// Set the statements emitted before the module's imports.
+    pub fn set_prelude(&mut self, prelude: Vec<block::Statement>) {
+        self.prelude = prelude;
+    }
+
+    /// Add a directive (eg. `"use client"`) before all other statements and imports.
+    pub fn add_directive(&mut self, directive: &str) {
+        self.prelude.insert(0, block::Statement::from_str_double_quoted(directive));
+    }
+
+    /// Add a type-only export list to the module (eg. `export type { Foo } from './foo'`).
+    #[cfg(feature = "typescript")]
+    pub fn add_type_export(&mut self, names: Vec<&str>, path: Option<&str>) {
+        self.main_block.ts_export_type(names.into_iter().map(block::ExportSpecifier::new).collect(), path);
+    }
+
+    /// Add the `"use client"` directive to the module.
+    pub fn add_use_client(&mut self) {
+        self.add_directive("use client");
+    }
+
+    /// Add the `"use server"` directive to the module.
+    pub fn add_use_server(&mut self) {
+        self.add_directive("use server");
+    }
+
+    /// Merge imports of the same path into a single import where possible, removing duplicated names
+    /// (eg. `import { foo } from 'bar'` added twice becomes a single import).
+    /// This is also done when generating the module's code, without modifying the module.
+    pub fn dedup_dependencies(&mut self) {
+        self.dependencies = self.deduplicated_dependencies();
+    }
+
+    fn deduplicated_dependencies(&self) -> Vec<Dependency> {
+        let mut dependencies = Vec::with_capacity(self.dependencies.len());
+        for dependency in &self.dependencies {
+            merge_dependency(&mut dependencies, dependency.clone());
+        }
+        dependencies
+    }
+
+    /// Reorder the module's imports, see `SortOptions`.
+    pub fn sort_dependencies(&mut self, options: &SortOptions) {
+        // Stable sort, so imports that compare equal keep their order.
+        self.dependencies.sort_by(|a, b| sort_key(a, options).cmp(&sort_key(b, options)));
+        if options.alphabetical {
+            for dependency in &mut self.dependencies {
+                dependency.imports.sort();
+            }
+        }
+    }
+
+    /// Generate the module's code with its imports reordered, see `SortOptions`.
+    pub fn generate_sorted(&self, options: SortOptions) -> Result<String, CodegenError> {
+        let mut module = self.clone();
+        module.sort_dependencies(&options);
+        module.to_js_string()
     }
 
     /// Add a dependency to the module.
@@ -32,54 +272,291 @@ impl Module {
     }
 
     /// Generate the module's code and write it to a file.
+    /// If source maps are enabled in the options, the source map is written next to it (eg. `foo.js.map`)
+    /// and referenced by a `//# sourceMappingURL` comment at the end of the code.
     /// Returns the path of the file that was written to.
-    pub fn generate(&self, path: &PathBuf) -> PathBuf {
-        // If path is a directory, append the module's name to the path.
+    pub fn generate(&self, path: &Path) -> Result<PathBuf, CodegenError> {
+        // If path is a directory, append the module's file name to the path.
         let path = if path.is_dir() {
-            path.join(format!("{}.js", self.name))
+            path.join(self.file_name(&self.options))
         } else {
-            path.clone()
+            path.to_path_buf()
         };
 
-        let file = std::fs::File::create(&path).unwrap();
-        let mut writer = std::io::BufWriter::new(file);
-        writer.write_all(self.generate_code_string().as_bytes()).unwrap();
+        if !self.options.source_map {
+            let mut writer = std::io::BufWriter::new(std::fs::File::create(&path)?);
+            self.generate_to(&mut writer)?;
+            writer.flush()?;
+            return Ok(path);
+        }
 
-        path
+        let file_name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        let (mut code, mut source_map) = self.generate_with_source_map()?;
+        if !code.is_empty() && !code.ends_with('\n') {
+            code.push('\n');
+        }
+        code.push_str(&format!("//# sourceMappingURL={}.map\n", file_name));
+        source_map.file = Some(file_name);
+
+        let mut map_path = path.clone().into_os_string();
+        map_path.push(".map");
+        write_file(&path, &code)?;
+        write_file(Path::new(&map_path), &source_map.to_json())?;
+        Ok(path)
+    }
+
+    /// Generate the module's code and a source map of the statements created with `Statement::with_source`.
+    pub fn generate_with_source_map(&self) -> Result<(String, SourceMap), CodegenError> {
+        let mut code = self.generate_header(&self.options)?;
+        let mut source_map = SourceMap::new();
+        let start = end_position((0, 0), &code);
+        let block = self.output_block(&self.options)?;
+        code.push_str(&block.generate_mapped(block.indent, &self.options, start, &mut source_map)?);
+        Ok((code, source_map))
+    }
+
+    /// Generate a TypeScript declaration file (`.d.ts`) for the module and write it to a file.
+    /// Returns the path of the file that was written to.
+    #[cfg(feature = "typescript")]
+    pub fn generate_dts(&self, path: &Path) -> Result<PathBuf, CodegenError> {
+        let options = self.options.clone().with_mode(crate::codegen::GenerationMode::TypeScriptDeclaration);
+        // If path is a directory, append the module's file name to the path.
+        let path = if path.is_dir() {
+            path.join(self.file_name(&options))
+        } else {
+            path.to_path_buf()
+        };
+
+        write_file(&path, &self.generate_code_string_with_options(&options)?)?;
+        Ok(path)
+    }
+
+    /// Get the name of the file the module is written to with the given options (eg. `foo.js` or `foo.d.ts`).
+    pub fn file_name(&self, options: &CodegenOptions) -> String {
+        format!("{}.{}", self.name, options.mode.extension())
     }
 
     /// Generate the module's code and write it to any output.
-    pub fn generate_to(&self, output: &mut impl std::io::Write) {
-        // Imports
-        for dependency in &self.dependencies {
-            output.write_all(&format!(
-                "import {{ {} }} from '{}';\n",
-                dependency.imports.join(", "),
-                dependency.path
-            ).as_bytes()).unwrap();
-        }
+    /// Statements of the main block are written as soon as they are generated.
+    pub fn generate_to(&self, output: &mut impl std::io::Write) -> Result<(), CodegenError> {
+        output.write_all(self.generate_header(&self.options)?.as_bytes())?;
+        self.output_block(&self.options)?.generate_to(output, &self.options)
+    }
+
+    /// Generate the module's code, measuring the time spent on each main block statement.
+    #[cfg(feature = "benchmarking")]
+    pub fn generate_profiled(&self) -> Result<ProfilingReport, CodegenError> {
+        let per_statement = self.main_block.statements.iter()
+            .map(|statement| {
+                let start = std::time::Instant::now();
+                statement.generate_indented(self.main_block.indent, &self.options)?;
+                Ok(start.elapsed())
+            })
+            .collect::<Result<_, CodegenError>>()?;
+
+        let start = std::time::Instant::now();
+        let output = self.to_js_string()?;
+        Ok(ProfilingReport {
+            total_time: start.elapsed(),
+            per_statement,
+            output,
+        })
+    }
 
-        // Main block
-        output.write_all(&self.main_block.generate().as_bytes()).unwrap();
+    /// Generate the module's code.
+    pub fn to_js_string(&self) -> Result<String, CodegenError> {
+        self.generate_code_string_with_options(&self.options)
     }
 
     /// Generate the module's code.
-    pub fn generate_code_string(&self) -> String {
+    #[deprecated(since = "0.1.0", note = "use to_js_string()")]
+    pub fn generate_code_string(&self) -> Result<String, CodegenError> {
+        self.to_js_string()
+    }
+
+    /// Generate the module's code using the given options instead of the module's options.
+    /// Declaration files only contain the exported declarations and type imports of the module.
+    pub fn generate_code_string_with_options(&self, options: &CodegenOptions) -> Result<String, CodegenError> {
+        let mut code = self.generate_header(options)?;
+        code.push_str(&self.output_block(options)?.generate_with_options(options)?);
+        Ok(code)
+    }
+
+    /// Get the main block as it is generated with the given options and the module's format.
+    fn output_block(&self, options: &CodegenOptions) -> Result<Cow<'_, block::Block>, CodegenError> {
+        if options.is_declaration() {
+            return Ok(Cow::Owned(self.declaration_block()?));
+        }
+        match self.format {
+            ModuleFormat::Esm => Ok(Cow::Borrowed(&self.main_block)),
+            ModuleFormat::CommonJs => Ok(Cow::Owned(self.commonjs_block()?))
+        }
+    }
+
+    /// Generate everything before the main block (shebang, prelude and imports).
+    fn generate_header(&self, options: &CodegenOptions) -> Result<String, CodegenError> {
         let mut code = String::new();
 
-        // Add the imports.
-        for dependency in &self.dependencies {
-            code.push_str(&format!(
-                "import {{ {} }} from '{}';\n",
-                dependency.imports.join(", "),
-                dependency.path
-            ));
+        if !options.is_declaration() {
+            // Add the shebang.
+            if let Some(interpreter) = &self.shebang {
+                code.push_str(&format!("#!{}\n", interpreter));
+            }
+
+            // Add the prelude, terminated like the statements of the main block.
+            let mut prelude = block::Block::new(0);
+            prelude.extend_stmts(self.prelude.clone());
+            code.push_str(&prelude.generate_with_options(options)?);
+        }
+
+        // Add the imports, CommonJS imports are a part of the main block.
+        if self.format == ModuleFormat::CommonJs && !options.is_declaration() {
+            return Ok(code);
+        }
+        for dependency in &self.deduplicated_dependencies() {
+            if options.is_declaration() && dependency.is_side_effect() {
+                continue;
+            }
+            code.push_str(&format!("{}{}", dependency.generate_with_options(options), options.newline()));
+        }
+
+        Ok(code)
+    }
+
+    /// Get a copy of the main block with only the statements that belong in a declaration file (see `declaration_exports`),
+    /// doc comments are kept if they document one of these statements.
+    fn declaration_block(&self) -> Result<block::Block, CodegenError> {
+        let mut declarations = block::Block::new(self.main_block.indent).with_context(self.main_block.context);
+        let statements = &self.main_block.statements;
+        for (index, statement) in statements.iter().enumerate() {
+            let documents_next = matches!(statement, block::Statement::DocComment(_))
+                && statements.get(index + 1).is_some_and(is_declaration_export);
+            if documents_next {
+                declarations.extend_stmts(vec![statement.clone()]);
+            } else if is_declaration_export(statement) {
+                declarations.extend_stmts(declaration_exports(statement)?);
+            }
+        }
+        Ok(declarations)
+    }
+
+    /// Get a copy of the main block using CommonJS imports and exports.
+    /// Imports are replaced by `require()` calls at the start of the block and exported names are collected
+    /// into `module.exports` at the end of it. Default exports of values other than classes are stored in
+    /// a `_default` constant, `export *` is not supported.
+    fn commonjs_block(&self) -> Result<block::Block, CodegenError> {
+        for statement in &self.main_block.statements {
+            if let Err(CodegenError::AwaitOutsideAsync) = block::BlockContext::Normal.check(statement) {
+                return Err(CodegenError::InvalidStatement {
+                    context: "Top-level `await` can't be generated as CommonJS".to_string()
+                });
+            }
+        }
+
+        let mut commonjs = block::Block::new(self.main_block.indent);
+        for dependency in self.deduplicated_dependencies().iter().filter(|dependency| !dependency.type_only) {
+            commonjs.stmt(block::Statement::Require {
+                binding: dependency.default.clone().or(dependency.namespace.clone()),
+                imports: dependency.imports.clone(),
+                path: dependency.path.clone()
+            })?;
+        }
+
+        let mut exports = Vec::new();
+        for statement in &self.main_block.statements {
+            match statement {
+                block::Statement::Export { declaration, is_default: false } => {
+                    let names = declared_names(declaration);
+                    if names.is_empty() {
+                        return Err(CodegenError::InvalidStatement {
+                            context: format!("Can't find the exported name of {:?}", declaration)
+                        });
+                    }
+                    exports.extend(names.iter().map(|name| block::ExportSpecifier::new(name)));
+                    commonjs.stmt((**declaration).clone())?;
+                },
+                block::Statement::Export { declaration, is_default: true } => {
+                    match &**declaration {
+                        block::Statement::ClassDecl { name, .. } => {
+                            exports.push(block::ExportSpecifier::aliased(name, "default"));
+                            commonjs.stmt((**declaration).clone())?;
+                        },
+                        value => {
+                            exports.push(block::ExportSpecifier::aliased("_default", "default"));
+                            commonjs.stmt(block::Statement::var_decl(block::VarType::Const, "_default", Some(value.clone())))?;
+                        }
+                    }
+                },
+                block::Statement::ExportList { names, from_path } => {
+                    if let Some(path) = from_path {
+                        commonjs.stmt(block::Statement::Require {
+                            binding: None,
+                            imports: names.iter().map(|name| name.local.clone()).collect(),
+                            path: path.clone()
+                        })?;
+                    }
+                    exports.extend(names.iter().cloned());
+                },
+                #[cfg(feature = "typescript")]
+                block::Statement::TsExportType { .. } => {},
+                block::Statement::ExportAll(path) => {
+                    return Err(CodegenError::InvalidStatement {
+                        context: format!("`export * from '{}'` can't be generated as CommonJS", path)
+                    });
+                },
+                statement => {
+                    commonjs.stmt(statement.clone())?;
+                }
+            }
+        }
+
+        if !exports.is_empty() {
+            commonjs.stmt(block::Statement::ModuleExports(exports))?;
         }
+        Ok(commonjs)
+    }
+}
+
+/// Builder constructing a module in a single expression (eg. `Module::builder("foo").statement(...).build()`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModuleBuilder {
+    module: Module,
+}
+
+impl ModuleBuilder {
+    /// Add a dependency to the module.
+    pub fn dependency(mut self, dependency: Dependency) -> Self {
+        self.module.dep(dependency);
+        self
+    }
+
+    /// Add multiple dependencies to the module.
+    pub fn dependencies(mut self, dependencies: Vec<Dependency>) -> Self {
+        self.module.deps(dependencies);
+        self
+    }
+
+    /// Add a statement to the module's main block, fails if it uses `yield`.
+    pub fn statement(mut self, statement: block::Statement) -> Result<Self, CodegenError> {
+        self.module.stmt(statement)?;
+        Ok(self)
+    }
 
-        // Add the main block.
-        code.push_str(&self.main_block.generate());
+    /// Add multiple statements to the module's main block.
+    pub fn statements(mut self, statements: Vec<block::Statement>) -> Self {
+        self.module.extend_stmts(statements);
+        self
+    }
+
+    /// Set the options used when generating the module's code.
+    pub fn options(mut self, options: CodegenOptions) -> Self {
+        self.module.set_options(options);
+        self
+    }
 
-        code
+    /// Finish building the module.
+    pub fn build(self) -> Module {
+        self.module
     }
 }
 
@@ -97,13 +574,52 @@ impl DerefMut for Module {
     }
 }
 
+impl std::fmt::Display for Module {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        crate::codegen::display_generated(f, self.to_js_string())
+    }
+}
+
+/// How `Module::generate_sorted` orders the module's imports.
+/// Side-effect only imports are always kept first, in their original order.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SortOptions {
+    /// Sort imports by their path.
+    pub by_path: bool,
+    /// Put named imports before default imports and default imports before namespace imports.
+    pub named_first: bool,
+    /// Put imports of packages before relative and absolute paths.
+    pub external_first: bool,
+    /// Sort the names of each import alphabetically.
+    pub alphabetical: bool,
+}
+
+/// Timings collected by `Module::generate_profiled`.
+#[cfg(feature = "benchmarking")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfilingReport {
+    /// Time spent generating the whole module.
+    pub total_time: std::time::Duration,
+    /// Time spent generating each statement of the main block.
+    pub per_statement: Vec<std::time::Duration>,
+    /// The generated code.
+    pub output: String,
+}
+
 /// Module dependency.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Dependency {
     /// List of imported things (eg. `import { foo, bar } from 'baz'` would be `["foo", "bar"]`).
     pub imports: Vec<String>,
+    /// Default import (eg. `import foo from 'baz'` would be `Some("foo")`).
+    pub default: Option<String>,
+    /// Namespace import alias (eg. `import * as foo from 'baz'` would be `Some("foo")`).
+    pub namespace: Option<String>,
     /// Path of the dependency (eg. `import { foo, bar } from 'baz'` would be `"baz"`).
     pub path: String,
+    /// Whether only types are imported (eg. `import type { Foo } from 'bar'`), these imports are erased from CommonJS output.
+    pub type_only: bool,
 }
 
 impl Dependency {
@@ -111,14 +627,340 @@ impl Dependency {
     pub fn new(imports: Vec<String>, path: &str) -> Self {
         Self {
             imports,
+            default: None,
+            namespace: None,
             path: path.to_string(),
+            type_only: false,
         }
     }
+
+    /// Create a new default import dependency (eg. `import foo from 'bar'`).
+    pub fn default(name: &str, path: &str) -> Self {
+        Self {
+            imports: Vec::new(),
+            default: Some(name.to_string()),
+            namespace: None,
+            path: path.to_string(),
+            type_only: false,
+        }
+    }
+
+    /// Create a new dependency with both default and named imports (eg. `import foo, { bar } from 'baz'`).
+    pub fn default_with_named(default: &str, named: Vec<String>, path: &str) -> Self {
+        Self {
+            imports: named,
+            default: Some(default.to_string()),
+            namespace: None,
+            path: path.to_string(),
+            type_only: false,
+        }
+    }
+
+    /// Create a new namespace import dependency (eg. `import * as foo from 'bar'`).
+    pub fn namespace(alias: &str, path: &str) -> Self {
+        Self {
+            imports: Vec::new(),
+            default: None,
+            namespace: Some(alias.to_string()),
+            path: path.to_string(),
+            type_only: false,
+        }
+    }
+
+    /// Create a new type-only import dependency (eg. `import type { Foo } from 'bar'`).
+    #[cfg(feature = "typescript")]
+    pub fn type_import(imports: Vec<String>, path: &str) -> Self {
+        Self {
+            type_only: true,
+            ..Self::new(imports, path)
+        }
+    }
+
+    /// Create a new side-effect only dependency (eg. `import 'bar'`).
+    pub fn side_effect(path: &str) -> Self {
+        Self::new(Vec::new(), path)
+    }
+
+    /// Merge another import of the same path into this one (eg. `import { a } from 'x'` and
+    /// `import { b } from 'x'` become `import { a, b } from 'x'`).
+    /// Returns `false` and leaves the dependency unchanged if the imports can't be combined into one.
+    pub fn merge(&mut self, other: &Dependency) -> bool {
+        if self.path != other.path || self.type_only != other.type_only {
+            return false;
+        }
+        let default = match (&self.default, &other.default) {
+            (Some(a), Some(b)) if a != b => return false,
+            (default, other_default) => default.clone().or(other_default.clone())
+        };
+        let namespace = match (&self.namespace, &other.namespace) {
+            (Some(a), Some(b)) if a != b => return false,
+            (namespace, other_namespace) => namespace.clone().or(other_namespace.clone())
+        };
+        let mut imports = self.imports.clone();
+        for import in &other.imports {
+            if !imports.contains(import) {
+                imports.push(import.clone());
+            }
+        }
+        // Namespace and named imports can't be used in the same statement.
+        if namespace.is_some() && !imports.is_empty() {
+            return false;
+        }
+
+        self.default = default;
+        self.namespace = namespace;
+        self.imports = imports;
+        true
+    }
+
+    /// Check if the dependency is imported only for its side effects (has no bindings).
+    pub fn is_side_effect(&self) -> bool {
+        self.imports.is_empty() && self.default.is_none() && self.namespace.is_none()
+    }
+
+    /// Generate the import statement for the dependency.
+    pub fn generate(&self) -> String {
+        self.generate_with_options(&CodegenOptions::default())
+    }
+
+    /// Generate the import statement for the dependency using the given options.
+    pub fn generate_with_options(&self, options: &CodegenOptions) -> String {
+        let path = options.quote_style.quote_literal(&self.path);
+        if self.is_side_effect() {
+            return format!("import {};", path);
+        }
+
+        // Type-only imports can't have both default and named bindings, so they are split in two.
+        let type_only = self.type_only || options.is_declaration();
+        if type_only && self.default.is_some() && (self.namespace.is_some() || !self.imports.is_empty()) {
+            let default = Dependency { imports: Vec::new(), namespace: None, ..self.clone() };
+            let named = Dependency { default: None, ..self.clone() };
+            return format!(
+                "{}{}{}",
+                default.generate_with_options(options),
+                options.newline(),
+                named.generate_with_options(options)
+            );
+        }
+
+        let mut bindings = Vec::new();
+        if let Some(default) = &self.default {
+            bindings.push(default.clone());
+        }
+        if let Some(namespace) = &self.namespace {
+            bindings.push(options.join_tokens(&["*", "as", namespace]));
+        }
+        let separator = format!(",{}", options.space());
+        if !self.imports.is_empty() {
+            let imports = self.imports.join(&separator);
+            bindings.push(options.join_tokens(&["{", &imports, "}"]));
+        }
+
+        let import = if type_only { "import type" } else { "import" };
+        format!("{};", options.join_tokens(&[import, &bindings.join(&separator), "from", &path]))
+    }
+}
+
+/// Check if the statement is exported in a way that can be described by a declaration file.
+fn is_declaration_export(statement: &block::Statement) -> bool {
+    match statement {
+        block::Statement::Export { .. } | block::Statement::ExportList { .. } | block::Statement::ExportAll(_) => true,
+        #[cfg(feature = "typescript")]
+        block::Statement::TsDeclare(_) | block::Statement::TsDeclareModule { .. } | block::Statement::TsExportType { .. } => true,
+        block::Statement::Commented { statement, .. } | block::Statement::Mapped { statement, .. } => {
+            is_declaration_export(statement)
+        },
+        _ => false
+    }
+}
+
+/// Get the statements describing an export in a declaration file.
+/// Default exports of values other than classes and names are declared as a `_default` constant typed by the value
+/// (eg. `declare const _default: number;`). Function bodies in exported raw code are replaced by `;`,
+/// other exported raw code fails as its type is unknown.
+fn declaration_exports(statement: &block::Statement) -> Result<Vec<block::Statement>, CodegenError> {
+    use block::Statement;
+
+    match statement {
+        Statement::Export { declaration, is_default: true } => match &**declaration {
+            Statement::ClassDecl { .. } | Statement::Identifier(_) => Ok(vec![statement.clone()]),
+            Statement::Raw(code) if validate::validate_identifier(code.trim()).is_ok() => {
+                Ok(vec![Statement::export_default(Statement::Identifier(code.trim().to_string()))])
+            },
+            value => Ok(vec![
+                Statement::Raw(format!("declare const _default: {};", value.declared_type())),
+                Statement::export_default(Statement::Identifier("_default".to_string()))
+            ])
+        },
+        Statement::Export { declaration, is_default: false } => match &**declaration {
+            Statement::Raw(code) => match function_signature(code) {
+                Some(signature) => Ok(vec![Statement::Export { declaration: Box::new(Statement::Raw(signature)), is_default: false }]),
+                None => Err(CodegenError::InvalidStatement {
+                    context: format!("Can't describe the exported raw code `{}` in a declaration file", code)
+                })
+            },
+            _ => Ok(vec![statement.clone()])
+        },
+        Statement::Commented { statement, comment } => {
+            let mut declarations = declaration_exports(statement)?;
+            if let Some(last) = declarations.pop() {
+                declarations.push(last.with_comment(comment));
+            }
+            Ok(declarations)
+        },
+        Statement::Mapped { statement, location } => {
+            let mut declarations = declaration_exports(statement)?;
+            if let Some(last) = declarations.pop() {
+                declarations.push(last.with_source(location.clone()));
+            }
+            Ok(declarations)
+        },
+        _ => Ok(vec![statement.clone()])
+    }
+}
+
+/// Get the signature of a function declaration in raw code, ending with `;` instead of the body
+/// (eg. `function foo(a, b);` for `async function foo(a, b) { ... }`). Returns `None` if the code isn't a function.
+/// The `async` and `*` modifiers are removed, as they aren't allowed in declaration files.
+fn function_signature(code: &str) -> Option<String> {
+    let code = code.trim();
+    let code = code.strip_prefix("async").filter(|rest| rest.starts_with(char::is_whitespace)).unwrap_or(code).trim_start();
+    let head = code.strip_prefix("function")
+        .filter(|rest| rest.starts_with(|c: char| c.is_whitespace() || c == '*'))?
+        .trim_start();
+    let head = head.strip_prefix('*').unwrap_or(head).trim_start();
+
+    let mut depth = 0;
+    let mut params_end = None;
+    for (i, c) in head.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 1 => {
+                params_end = Some(i);
+                break;
+            },
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    let body_start = params_end.and_then(|end| head[end..].find('{').map(|start| end + start))?;
+    Some(format!("function {};", head[..body_start].trim_end()))
+}
+
+/// Get the names declared by a statement, the name in raw code is found after keywords like `function` or `const`.
+fn declared_names(statement: &block::Statement) -> Vec<String> {
+    match statement {
+        block::Statement::VarDecl { name, .. } | block::Statement::ClassDecl { name, .. } => vec![name.clone()],
+        block::Statement::MultiVarDecl { declarators, .. } => declarators.iter().map(|(name, _)| name.clone()).collect(),
+        block::Statement::DestructureDecl { pattern, .. } => pattern.bindings().into_iter().map(str::to_string).collect(),
+        block::Statement::Commented { statement, .. } | block::Statement::Mapped { statement, .. } => declared_names(statement),
+        block::Statement::Raw(code) => code
+            .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+            .find(|word| !word.is_empty() && !matches!(*word, "async" | "function" | "class" | "const" | "let" | "var"))
+            .map(|name| name.to_string())
+            .into_iter()
+            .collect(),
+        _ => Vec::new()
+    }
+}
+
+/// Get the part of a statement that is kept by `Module::tree_shake` regardless of other statements.
+/// Export lists are filtered to the entry exports, unrelated statements give `None`.
+fn shake_root(statement: &block::Statement, entry_exports: &[&str]) -> Option<block::Statement> {
+    use block::Statement;
+
+    let is_entry = |name: &str| entry_exports.contains(&name);
+    let filter_specifiers = |names: &[block::ExportSpecifier]| -> Vec<block::ExportSpecifier> {
+        names.iter()
+            .filter(|specifier| is_entry(specifier.exported.as_deref().unwrap_or(&specifier.local)))
+            .cloned()
+            .collect()
+    };
+    match statement {
+        Statement::Export { is_default: true, .. } => is_entry("default").then(|| statement.clone()),
+        Statement::Export { declaration, .. } => {
+            declaration.collect_declarations().into_iter().any(is_entry).then(|| statement.clone())
+        },
+        Statement::ExportList { names, from_path } => {
+            let names = filter_specifiers(names);
+            (!names.is_empty()).then(|| Statement::ExportList { names, from_path: from_path.clone() })
+        },
+        Statement::ModuleExports(names) => {
+            let names = filter_specifiers(names);
+            (!names.is_empty()).then_some(Statement::ModuleExports(names))
+        },
+        Statement::Commented { statement, comment } => shake_root(statement, entry_exports)
+            .map(|statement| Statement::Commented { statement: Box::new(statement), comment: comment.clone() }),
+        Statement::Mapped { statement, location } => shake_root(statement, entry_exports)
+            .map(|statement| Statement::Mapped { statement: Box::new(statement), location: location.clone() }),
+        Statement::Raw(_) | Statement::ExportAll(_) => Some(statement.clone()),
+        #[cfg(feature = "typescript")]
+        Statement::TsDeclare(_) | Statement::TsDeclareModule { .. } | Statement::TsInterface { .. }
+            | Statement::TsTypeAlias { .. } | Statement::TsExportType { .. } => Some(statement.clone()),
+        _ => None
+    }
+}
+
+/// Check if the statement exports something, exports are only kept by `Module::tree_shake` if they are entries.
+fn is_export(statement: &block::Statement) -> bool {
+    match statement {
+        block::Statement::Export { declaration, .. } => declaration.collect_declarations().is_empty(),
+        block::Statement::ExportList { .. } | block::Statement::ModuleExports(_) => true,
+        block::Statement::Commented { statement, .. } | block::Statement::Mapped { statement, .. } => is_export(statement),
+        _ => false
+    }
+}
+
+/// Get the key imports are ordered by in `Module::sort_dependencies`.
+fn sort_key<'a>(dependency: &'a Dependency, options: &SortOptions) -> (bool, u8, bool, &'a str) {
+    // Side effects may depend on each other, so their order is kept.
+    if dependency.is_side_effect() {
+        return (false, 0, false, "");
+    }
+    let kind = match (&dependency.namespace, &dependency.default) {
+        _ if !options.named_first => 0,
+        (None, None) => 0,
+        (None, Some(_)) => 1,
+        (Some(_), _) => 2
+    };
+    let is_relative = dependency.path.starts_with('.') || dependency.path.starts_with('/');
+    (
+        true,
+        kind,
+        options.external_first && is_relative,
+        if options.by_path { &dependency.path } else { "" }
+    )
+}
+
+/// Write the code to a file, replacing its contents.
+fn write_file(path: &Path, code: &str) -> Result<(), CodegenError> {
+    let file = std::fs::File::create(path)?;
+    let mut writer = std::io::BufWriter::new(file);
+    writer.write_all(code.as_bytes())?;
+    Ok(())
+}
+
+/// Check if the code contains the identifier as a whole word.
+fn contains_identifier(code: &str, identifier: &str) -> bool {
+    let is_identifier_char = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    code.match_indices(identifier).any(|(start, _)| {
+        let before = code[..start].chars().next_back();
+        let after = code[start + identifier.len()..].chars().next();
+        !before.is_some_and(is_identifier_char) && !after.is_some_and(is_identifier_char)
+    })
+}
+
+/// Add a dependency to the list, merging it into an existing import of the same path if possible.
+fn merge_dependency(dependencies: &mut Vec<Dependency>, dependency: Dependency) {
+    if !dependencies.iter_mut().any(|existing| existing.merge(&dependency)) {
+        dependencies.push(dependency);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::codegen::QuoteStyle;
 
     #[test]
     fn test_module_with_dependencies() {
@@ -127,7 +969,7 @@ mod tests {
         module.dep(Dependency::new(vec!["bar".to_string()], "baz"));
 
         assert_eq!(
-            module.generate_code_string(),
+            module.to_js_string().unwrap(),
             "import { foo } from 'bar';\nimport { bar } from 'baz';\n"
         );
     }
@@ -135,9 +977,643 @@ mod tests {
     #[test]
     fn test_module_with_main_block() {
         let mut module = Module::create("foo");
-        module.stmt(block::Statement::Raw("foo".to_string()));
+        module.stmt(block::Statement::Raw("foo".to_string())).unwrap();
         module.dep(Dependency::new(vec!["foo".to_string()], "bar"));
 
-        assert_eq!(module.generate_code_string(), "import { foo } from 'bar';\nfoo\n");
+        assert_eq!(module.to_js_string().unwrap(), "import { foo } from 'bar';\nfoo\n");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_module_with_default_dependencies() {
+        let mut module = Module::create("foo");
+        module.dep(Dependency::default("preact", "preact"));
+        module.dep(Dependency::default_with_named("React", vec!["useState".to_string()], "react"));
+
+        assert_eq!(
+            module.to_js_string().unwrap(),
+            "import preact from 'preact';\nimport React, { useState } from 'react';\n"
+        );
+    }
+
+    #[test]
+    fn test_module_with_namespace_dependencies() {
+        let mut module = Module::create("foo");
+        module.dep(Dependency::namespace("fs", "node:fs"));
+        module.dep(Dependency::new(vec!["join".to_string()], "node:path"));
+
+        assert_eq!(
+            module.to_js_string().unwrap(),
+            "import * as fs from 'node:fs';\nimport { join } from 'node:path';\n"
+        );
+    }
+
+    #[test]
+    fn test_module_with_side_effect_dependency() {
+        let mut module = Module::create("foo");
+        module.dep(Dependency::side_effect("./polyfill.js"));
+
+        assert_eq!(module.to_js_string().unwrap(), "import './polyfill.js';\n");
+    }
+
+    #[test]
+    fn test_module_with_directive() {
+        let mut module = Module::create("foo");
+        module.dep(Dependency::new(vec!["foo".to_string()], "bar"));
+        module.raw("foo");
+        module.add_use_client();
+
+        assert_eq!(module.to_js_string().unwrap(), "\"use client\";\nimport { foo } from 'bar';\nfoo\n");
+
+        // Directives of the module and of a block are generated the same way.
+        let mut block = block::Block::new(0);
+        block.add_use_client();
+        assert!(module.to_js_string().unwrap().starts_with(&block.generate().unwrap()));
+        let options = CodegenOptions::new().with_semicolons(crate::codegen::SemicolonMode::Never);
+        module.set_options(options.clone());
+        assert_eq!(block.generate_with_options(&options).unwrap(), "\"use client\"\n");
+        assert!(module.to_js_string().unwrap().starts_with("\"use client\"\nimport"));
+    }
+
+    #[test]
+    #[cfg(feature = "benchmarking")]
+    fn test_module_generate_profiled() {
+        let mut module = Module::create("foo");
+        module.raw("foo");
+        module.raw("bar");
+
+        let report = module.generate_profiled().unwrap();
+        assert_eq!(report.output, module.to_js_string().unwrap());
+        assert_eq!(report.per_statement.len(), 2);
+    }
+
+    #[test]
+    fn test_module_with_shebang() {
+        let mut module = Module::create("foo");
+        module.add_use_client();
+        module.dep(Dependency::new(vec!["foo".to_string()], "bar"));
+        module.add_shebang("/usr/bin/env node");
+
+        assert_eq!(
+            module.to_js_string().unwrap(),
+            "#!/usr/bin/env node\n\"use client\";\nimport { foo } from 'bar';\n"
+        );
+    }
+
+    #[test]
+    fn test_module_with_double_quotes() {
+        let mut module = Module::create("foo");
+        module.set_options(CodegenOptions::new().with_quote_style(QuoteStyle::Double));
+        module.dep(Dependency::new(vec!["foo".to_string()], "bar"));
+        module.dep(Dependency::side_effect("baz"));
+        module.var_decl(block::VarType::Const, "name", Some("it's".into())).unwrap();
+
+        assert_eq!(
+            module.to_js_string().unwrap(),
+            "import { foo } from \"bar\";\nimport \"baz\";\nconst name = \"it's\";\n"
+        );
+    }
+
+    #[test]
+    fn test_module_with_backticks() {
+        let mut module = Module::create("foo");
+        module.set_options(CodegenOptions::new().with_quote_style(QuoteStyle::Backtick));
+        module.dep(Dependency::new(vec!["foo".to_string()], "bar"));
+        module.dep(Dependency::side_effect("baz"));
+        module.var_decl(block::VarType::Const, "name", Some("it's".into())).unwrap();
+        module.export_list(vec![block::ExportSpecifier::new("name")], Some("./name.js"));
+        module.stmt(block::Statement::ExportAll("./all.js".to_string())).unwrap();
+
+        assert_eq!(
+            module.to_js_string().unwrap(),
+            concat!(
+                "import { foo } from 'bar';\n",
+                "import 'baz';\n",
+                "const name = `it's`;\n",
+                "export { name } from './name.js';\n",
+                "export * from './all.js';\n"
+            )
+        );
+
+        module.set_module_format(ModuleFormat::CommonJs);
+        assert!(module.to_js_string().is_err());
+        module.main_block.statements.pop();
+        assert_eq!(
+            module.to_js_string().unwrap(),
+            concat!(
+                "const { foo } = require('bar');\n",
+                "require('baz');\n",
+                "const name = `it's`;\n",
+                "const { name } = require('./name.js');\n",
+                "module.exports = { name };\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_module_with_prelude() {
+        let mut module = Module::create("foo");
+        module.set_prelude(vec![
+            block::Statement::Raw("/// <reference types=\"vite/client\" />".to_string()),
+            block::Statement::Raw("// @ts-nocheck".to_string()),
+        ]);
+        module.dep(Dependency::new(vec!["foo".to_string()], "bar"));
+
+        let mut output = Vec::new();
+        module.generate_to(&mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "/// <reference types=\"vite/client\" />\n// @ts-nocheck\nimport { foo } from 'bar';\n"
+        );
+    }
+
+    #[test]
+    fn test_barrel_modules() {
+        let module = Module::create_barrel("index", &[("foo", "./foo.js"), ("bar", "./bar.js")]);
+        assert_eq!(module.to_js_string().unwrap(), "export { foo } from './foo.js';\nexport { bar } from './bar.js';\n");
+
+        let module = Module::create_barrel_all("index", &["./foo.js", "./bar.js"]);
+        assert_eq!(module.to_js_string().unwrap(), "export * from './foo.js';\nexport * from './bar.js';\n");
+    }
+
+    #[test]
+    fn test_minified_module() {
+        let mut module = Module::create("foo");
+        module.set_options(CodegenOptions::new().with_minify(true));
+        module.add_use_client();
+        module.dep(Dependency::default_with_named("React", vec!["useState".to_string()], "react"));
+        module.dep(Dependency::namespace("path", "node:path"));
+        module.var_decl(block::VarType::Const, "x", Some(1.into())).unwrap();
+        assert_eq!(
+            module.to_js_string().unwrap(),
+            "\"use client\";import React,{useState}from'react';import*as path from'node:path';const x=1;"
+        );
+    }
+
+    #[test]
+    fn test_module_display() {
+        let mut module = Module::create("foo");
+        module.dep(Dependency::default("bar", "./bar.js"));
+        module.raw("bar()");
+        assert_eq!(module.to_string(), module.to_js_string().unwrap());
+
+        module.stmt(block::Statement::Identifier("not valid".to_string())).unwrap();
+        assert_eq!(module.to_string(), "/* Invalid identifier `not valid` */");
+    }
+
+    #[test]
+    fn test_merge_modules() {
+        let mut a = Module::create("a");
+        a.dep(Dependency::new(vec!["foo".to_string()], "./lib.js"));
+        a.raw("foo()");
+        let mut b = Module::create("b");
+        b.dep(Dependency::new(vec!["foo".to_string()], "./lib.js"));
+        b.raw("foo(1)");
+
+        let merged = a.merge(b);
+        assert_eq!(merged.name, "a");
+        assert_eq!(merged.to_js_string().unwrap(), "import { foo } from './lib.js';\nfoo()\nfoo(1)\n");
+    }
+
+    #[test]
+    fn test_merge_modules_with_different_deps() {
+        let mut a = Module::create("a");
+        a.dep(Dependency::new(vec!["foo".to_string()], "./lib.js"));
+        a.dep(Dependency::namespace("path", "node:path"));
+        let mut b = Module::create("b");
+        b.dep(Dependency::default_with_named("lib", vec!["bar".to_string(), "foo".to_string()], "./lib.js"));
+        b.dep(Dependency::new(vec!["join".to_string()], "node:path"));
+        b.dep(Dependency::side_effect("./polyfill.js"));
+
+        let merged = a.merge(b);
+        assert_eq!(
+            merged.to_js_string().unwrap(),
+            concat!(
+                "import lib, { foo, bar } from './lib.js';\n",
+                "import * as path from 'node:path';\n",
+                "import { join } from 'node:path';\n",
+                "import './polyfill.js';\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_generate_io_error() {
+        let module = Module::create("foo");
+        let result = module.generate(Path::new("/nonexistent-directory/foo.js"));
+        assert!(matches!(result, Err(CodegenError::IoError(_))));
+    }
+
+    #[test]
+    fn test_unused_imports() {
+        let mut module = Module::create("foo");
+        module.dep(Dependency::new(vec!["foo".to_string(), "bar".to_string()], "./lib.js"));
+        module.dep(Dependency::default("React", "react"));
+        module.raw("foo(barista)");
+        module.stmt(block::Statement::Await(Box::new(block::Statement::Raw("React.lazy()".to_string())))).unwrap();
+
+        let unused = module.unused_imports();
+        assert_eq!(unused.len(), 1);
+        assert_eq!(unused[0].0.path, "./lib.js");
+        assert_eq!(unused[0].1, "bar");
+    }
+
+    #[test]
+    #[cfg(feature = "typescript")]
+    fn test_generate_dts() {
+        use crate::module::class::ClassMember;
+
+        let mut method_body = block::Block::new(0);
+        method_body.raw("return this.x");
+        let mut class = block::Block::new(0);
+        class.class_decl("Point", None, vec![
+            ClassMember::field("x", Some(1.into())),
+            ClassMember::private_field("secret", None),
+            ClassMember::method("getX", Vec::new(), method_body),
+        ]).unwrap();
+
+        let mut module = Module::create("point");
+        module.add_shebang("/usr/bin/env node");
+        module.dep(Dependency::default_with_named("React", vec!["useState".to_string()], "react"));
+        module.dep(Dependency::side_effect("./polyfill.js"));
+        module.raw("console.log('internal')");
+        module.stmt(block::Statement::DocComment("The origin.".to_string())).unwrap();
+        module.export(block::Statement::var_decl(block::VarType::Const, "ORIGIN", Some(0.into())));
+        module.export(block::Statement::var_decl(block::VarType::Const, "now", Some(block::Statement::Raw("Date.now()".to_string()))));
+        module.export(block::Statement::var_decl(block::VarType::Let, "count", Some(1.into())));
+        module.export(block::Statement::MultiVarDecl {
+            var_type: block::VarType::Let,
+            declarators: vec![("label".to_string(), Some(Box::new("foo".into()))), ("flag".to_string(), None)]
+        });
+        module.export(class.statements.remove(0));
+        module.export(block::Statement::Raw("async function load(url, { retries } = {}) { return fetch(url) }".to_string()));
+        module.export_default(block::Statement::Raw("ORIGIN".to_string()));
+
+        let path = std::env::temp_dir().join("nauvi_test_generate_dts.d.ts");
+        assert_eq!(module.generate_dts(&path).unwrap(), path);
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            concat!(
+                "import type React from 'react';\n",
+                "import type { useState } from 'react';\n",
+                "/**\n",
+                " * The origin.\n",
+                " */\n",
+                "export const ORIGIN = 0;\n",
+                "export const now: unknown;\n",
+                "export let count: number;\n",
+                "export let label: string, flag;\n",
+                "export class Point {\n",
+                "    x: number;\n",
+                "    getX();\n",
+                "}\n",
+                "export function load(url, { retries } = {});\n",
+                "export default ORIGIN;\n"
+            )
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_declaration_default_exports_and_raw_code() {
+        let options = CodegenOptions::new().with_mode(crate::codegen::GenerationMode::TypeScriptDeclaration);
+        let mut module = Module::create("config");
+        module.export_default(block::Statement::from(42));
+        assert_eq!(
+            module.generate_code_string_with_options(&options).unwrap(),
+            "declare const _default: number;\nexport default _default;\n"
+        );
+
+        let mut module = Module::create("config");
+        module.export(block::Statement::Raw("const config = load()".to_string()));
+        let result = module.generate_code_string_with_options(&options);
+        assert!(matches!(result, Err(CodegenError::InvalidStatement { context }) if context.contains("const config = load()")));
+    }
+
+    #[test]
+    #[cfg(feature = "typescript")]
+    fn test_type_imports_and_exports() {
+        let mut module = Module::create("types");
+        module.set_options(CodegenOptions::new().with_mode(crate::codegen::GenerationMode::TypeScript));
+        module.dep(Dependency::type_import(vec!["Props".to_string()], "./props"));
+        module.dep(Dependency::new(vec!["render".to_string()], "./props"));
+        module.dep(Dependency::type_import(vec!["State".to_string()], "./props"));
+        module.add_type_export(vec!["Props", "State"], None);
+        module.add_type_export(vec!["Theme"], Some("./theme"));
+        module.export(block::Statement::var_decl(block::VarType::Const, "VERSION", Some(1.into())));
+
+        let expected = concat!(
+            "import type { Props, State } from './props';\n",
+            "import { render } from './props';\n",
+            "export type { Props, State };\n",
+            "export type { Theme } from './theme';\n",
+            "export const VERSION = 1;\n"
+        );
+        assert_eq!(module.to_js_string().unwrap(), expected);
+        let options = CodegenOptions::new().with_mode(crate::codegen::GenerationMode::TypeScriptDeclaration);
+        assert_eq!(
+            module.generate_code_string_with_options(&options).unwrap(),
+            expected.replace("import { render }", "import type { render }")
+        );
+
+        module.set_module_format(ModuleFormat::CommonJs);
+        assert_eq!(
+            module.to_js_string().unwrap(),
+            "const { render } = require('./props');\nconst VERSION = 1;\nmodule.exports = { VERSION };\n"
+        );
+    }
+
+    #[test]
+    fn test_generate_with_source_map() {
+        use crate::codegen::sourcemap::SourceLocation;
+
+        let mut module = Module::create("app");
+        module.set_options(CodegenOptions::new().with_source_map(true));
+        module.dep(Dependency::new(vec!["log".to_string()], "./log.js"));
+        module.stmt(block::Statement::var_decl(block::VarType::Let, "count", Some(0.into()))
+            .with_source(SourceLocation::new("app.src", 0, 0).with_name("counter"))).unwrap();
+        let mut nested = block::Block::new(1);
+        nested.stmt(block::Statement::Raw("log(count)".to_string()).with_source(SourceLocation::new("app.src", 2, 4))).unwrap();
+        module.stmt(block::Statement::Block(Box::new(nested))).unwrap();
+
+        let (code, source_map) = module.generate_with_source_map().unwrap();
+        assert_eq!(code, module.to_js_string().unwrap());
+        assert_eq!(source_map.encode_mappings(), ";AAAAA;IAEI");
+
+        let path = std::env::temp_dir().join("nauvi_test_generate_with_source_map.js");
+        module.generate(&path).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            format!("{}//# sourceMappingURL=nauvi_test_generate_with_source_map.js.map\n", code)
+        );
+        let map_path = std::env::temp_dir().join("nauvi_test_generate_with_source_map.js.map");
+        assert_eq!(
+            std::fs::read_to_string(&map_path).unwrap(),
+            concat!(
+                "{\"version\":3,\"file\":\"nauvi_test_generate_with_source_map.js\",",
+                "\"sources\":[\"app.src\"],\"names\":[\"counter\"],\"mappings\":\";AAAAA;IAEI\"}"
+            )
+        );
+        std::fs::remove_file(path).unwrap();
+        std::fs::remove_file(map_path).unwrap();
+    }
+
+    #[test]
+    fn test_commonjs_module() {
+        let mut module = Module::create("foo");
+        module.set_module_format(ModuleFormat::CommonJs);
+        module.dep(Dependency::new(vec!["foo".to_string(), "bar as baz".to_string()], "./lib.js"));
+        module.dep(Dependency::new(vec!["join".to_string()], "node:path"));
+        module.dep(Dependency::default_with_named("React", vec!["useState".to_string()], "react"));
+        module.dep(Dependency::side_effect("./polyfill.js"));
+        module.export(block::Statement::var_decl(block::VarType::Const, "answer", Some(42.into())));
+        module.export(block::Statement::Raw("function greet() {}".to_string()));
+        module.stmt(block::Statement::ExportList {
+            names: vec![block::ExportSpecifier::aliased("foo", "qux")],
+            from_path: None
+        }).unwrap();
+        module.export_default(block::Statement::Raw("greet".to_string()));
+
+        assert_eq!(
+            module.to_js_string().unwrap(),
+            concat!(
+                "const { foo, bar: baz } = require('./lib.js');\n",
+                "const { join } = require('node:path');\n",
+                "const React = require('react'), { useState } = React;\n",
+                "require('./polyfill.js');\n",
+                "const answer = 42;\n",
+                "function greet() {}\n",
+                "const _default = greet;\n",
+                "module.exports = { answer, greet, qux: foo, default: _default };\n"
+            )
+        );
+
+        module.set_options(CodegenOptions::new().with_minify(true));
+        assert_eq!(
+            module.to_js_string().unwrap(),
+            concat!(
+                "const{foo,bar:baz}=require('./lib.js');const{join}=require('node:path');",
+                "const React=require('react'),{useState}=React;require('./polyfill.js');",
+                "const answer=42;function greet() {};const _default=greet;",
+                "module.exports={answer,greet,qux:foo,default:_default};"
+            )
+        );
+
+        let mut module = Module::create_barrel_all("index", &["./foo.js"]);
+        module.set_module_format(ModuleFormat::CommonJs);
+        assert!(matches!(module.to_js_string(), Err(CodegenError::InvalidStatement { .. })));
+    }
+
+    #[test]
+    fn test_commonjs_multi_var_export() {
+        let mut module = Module::create("foo");
+        module.set_module_format(ModuleFormat::CommonJs);
+        module.export(block::Statement::MultiVarDecl {
+            var_type: block::VarType::Let,
+            declarators: vec![("min".to_string(), Some(Box::new(0.into()))), ("max".to_string(), None)]
+        });
+        assert_eq!(module.to_js_string().unwrap(), "let min = 0, max;\nmodule.exports = { min, max };\n");
+    }
+
+    #[test]
+    fn test_commonjs_top_level_await() {
+        let mut module = Module::create("foo");
+        module.var_decl(block::VarType::Const, "data", Some(block::Statement::Await(Box::new(block::Statement::Raw("load()".to_string()))))).unwrap();
+        assert_eq!(module.to_js_string().unwrap(), "const data = await load();\n");
+
+        module.set_module_format(ModuleFormat::CommonJs);
+        let result = module.to_js_string();
+        assert!(matches!(result, Err(CodegenError::InvalidStatement { context }) if context.contains("Top-level `await`")));
+    }
+
+    #[test]
+    fn test_require_and_exports() {
+        let mut block = block::Block::new(0);
+        block.require(vec!["readFile", "writeFile as write"], "node:fs");
+        block.exports(vec!["readFile", "write"]);
+        assert_eq!(
+            block.generate().unwrap(),
+            "const { readFile, writeFile: write } = require('node:fs');\nmodule.exports = { readFile, write };\n"
+        );
+    }
+
+    #[test]
+    fn test_generate_to_streams_statements() {
+        let mut module = Module::create("foo");
+        module.dep(Dependency::new(vec!["foo".to_string()], "bar"));
+        for i in 0..1000 {
+            module.raw(&format!("foo({})", i));
+        }
+
+        let mut output = Vec::new();
+        module.generate_to(&mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), module.to_js_string().unwrap());
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_generate_code_string_alias() {
+        let mut module = Module::create("foo");
+        module.raw("foo()");
+        assert_eq!(module.generate_code_string().unwrap(), module.to_js_string().unwrap());
+    }
+
+    #[test]
+    fn test_module_builder() {
+        let options = CodegenOptions::new().with_quote_style(QuoteStyle::Double);
+        let built = Module::builder("foo")
+            .dependency(Dependency::new(vec!["foo".to_string()], "bar"))
+            .dependencies(vec![Dependency::default("React", "react")])
+            .statement(block::Statement::Raw("foo()".to_string())).unwrap()
+            .statements(vec![block::Statement::Null, block::Statement::Undefined])
+            .options(options.clone())
+            .build();
+
+        let mut module = Module::create("foo");
+        module.dep(Dependency::new(vec!["foo".to_string()], "bar"));
+        module.deps(vec![Dependency::default("React", "react")]);
+        module.raw("foo()");
+        module.extend_stmts(vec![block::Statement::Null, block::Statement::Undefined]);
+        module.set_options(options);
+
+        assert_eq!(built, module);
+        assert_eq!(built.to_js_string().unwrap(), "import { foo } from \"bar\";\nimport React from \"react\";\nfoo()\nnull;\nundefined;\n");
+    }
+
+    #[test]
+    fn test_dedup_dependencies() {
+        let mut module = Module::create("foo");
+        for _ in 0..3 {
+            module.dep(Dependency::new(vec!["foo".to_string()], "bar"));
+        }
+        module.dep(Dependency::new(vec!["baz".to_string()], "bar"));
+        module.dep(Dependency::namespace("fs", "node:fs"));
+        module.dep(Dependency::namespace("fs", "node:fs"));
+
+        assert_eq!(module.to_js_string().unwrap(), "import { foo, baz } from 'bar';\nimport * as fs from 'node:fs';\n");
+        let mut output = Vec::new();
+        module.generate_to(&mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), module.to_js_string().unwrap());
+
+        assert_eq!(module.dependencies.len(), 6);
+        module.dedup_dependencies();
+        assert_eq!(module.dependencies, vec![
+            Dependency::new(vec!["foo".to_string(), "baz".to_string()], "bar"),
+            Dependency::namespace("fs", "node:fs"),
+        ]);
+    }
+
+    #[test]
+    fn test_generate_sorted() {
+        let mut module = Module::create("foo");
+        module.dep(Dependency::namespace("utils", "./utils.js"));
+        module.dep(Dependency::default("React", "react"));
+        module.dep(Dependency::side_effect("./polyfill.js"));
+        module.dep(Dependency::new(vec!["join".to_string(), "dirname".to_string()], "node:path"));
+        module.dep(Dependency::side_effect("./a.css"));
+        module.dep(Dependency::new(vec!["b".to_string(), "a".to_string()], "./lib.js"));
+
+        let sorted = module.generate_sorted(SortOptions { by_path: true, ..Default::default() }).unwrap();
+        assert_eq!(sorted, concat!(
+            "import './polyfill.js';\n",
+            "import './a.css';\n",
+            "import { b, a } from './lib.js';\n",
+            "import * as utils from './utils.js';\n",
+            "import { join, dirname } from 'node:path';\n",
+            "import React from 'react';\n"
+        ));
+
+        let sorted = module.generate_sorted(SortOptions {
+            by_path: true,
+            named_first: true,
+            external_first: true,
+            alphabetical: true
+        }).unwrap();
+        assert_eq!(sorted, concat!(
+            "import './polyfill.js';\n",
+            "import './a.css';\n",
+            "import { dirname, join } from 'node:path';\n",
+            "import { a, b } from './lib.js';\n",
+            "import React from 'react';\n",
+            "import * as utils from './utils.js';\n"
+        ));
+
+        // The module itself is not modified.
+        assert_eq!(module.dependencies[0], Dependency::namespace("utils", "./utils.js"));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde_roundtrip() {
+        let mut module = Module::create("app");
+        module.add_use_client();
+        module.dep(Dependency::default_with_named("React", vec!["useState".to_string()], "react"));
+        module.var_decl(block::VarType::Const, "count", Some(block::Statement::Identifier("useState".to_string()).call(vec![0.into()]))).unwrap();
+        module.class_decl("Counter", None, vec![
+            crate::module::class::ClassMember::field("step", Some(1.into())),
+        ]).unwrap();
+        module.export_list(vec![block::ExportSpecifier::aliased("Counter", "default")], None);
+        module.set_options(CodegenOptions::new().with_quote_style(crate::codegen::QuoteStyle::Double));
+
+        let json = serde_json::to_string(&module).unwrap();
+        assert!(json.starts_with("{\"name\":\"app\",\"dependencies\":"));
+        assert!(json.contains("\"indent\":0,\"statements\":["));
+        let deserialized: Module = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, module);
+        assert_eq!(deserialized.to_js_string().unwrap(), module.to_js_string().unwrap());
+    }
+
+    #[test]
+    fn test_tree_shake() {
+        let mut module = Module::create("lib");
+        module.dep(Dependency::new(vec!["helper".to_string()], "./helper.js"));
+        module.var_decl(block::VarType::Const, "base", Some(1.into())).unwrap();
+        module.var_decl(block::VarType::Const, "unused", Some(2.into())).unwrap();
+        module.var_decl(block::VarType::Const, "double", Some(block::Statement::Binary {
+            left: Box::new(block::Statement::Identifier("base".to_string())),
+            operator: "*".to_string(),
+            right: Box::new(2.into())
+        })).unwrap();
+        module.stmt(block::Statement::Identifier("sideEffect".to_string()).call(vec![])).unwrap();
+        module.export(block::Statement::var_decl(block::VarType::Const, "answer",
+            Some(block::Statement::Identifier("helper".to_string()).call(vec![block::Statement::Identifier("double".to_string())]))));
+        module.export(block::Statement::var_decl(block::VarType::Const, "other", Some(3.into())));
+        module.export_list(vec![block::ExportSpecifier::aliased("double", "twice"), block::ExportSpecifier::new("unused")], None);
+
+        let shaken = module.tree_shake(&["answer"]);
+        assert_eq!(shaken.to_js_string().unwrap(), concat!(
+            "import { helper } from './helper.js';\n",
+            "const base = 1;\n",
+            "const double = base * 2;\n",
+            "export const answer = helper(double);\n"
+        ));
+        assert_eq!(module.tree_shake(&["twice"]).to_js_string().unwrap(), concat!(
+            "import { helper } from './helper.js';\n",
+            "const base = 1;\n",
+            "const double = base * 2;\n",
+            "export { double as twice };\n"
+        ));
+        assert_eq!(module.tree_shake(&[]).to_js_string().unwrap(), "import { helper } from './helper.js';\n");
+    }
+
+    #[test]
+    fn test_tree_shake_keeps_uses_of_live_names() {
+        let ident = |name: &str| block::Statement::Identifier(name.to_string());
+        let mut module = Module::create("counter");
+        module.var_decl(block::VarType::Let, "count", Some(0.into())).unwrap();
+        module.var_decl(block::VarType::Const, "config", Some(1.into())).unwrap();
+        module.var_decl(block::VarType::Let, "unused", Some(2.into())).unwrap();
+        module.assign("count", "+=", 1);
+        module.stmt(ident("init").call(vec![ident("config"), ident("count")])).unwrap();
+        module.assign("unused", "=", 3);
+        module.export_list(vec![block::ExportSpecifier::new("count")], None);
+        module.export_list(vec![block::ExportSpecifier::aliased("count", "other")], None);
+
+        assert_eq!(module.tree_shake(&["count"]).to_js_string().unwrap(), concat!(
+            "let count = 0;\n",
+            "const config = 1;\n",
+            "count += 1;\n",
+            "init(config, count);\n",
+            "export { count };\n"
+        ));
+    }
+}
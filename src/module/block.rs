@@ -1,14 +1,84 @@
+use std::collections::HashMap;
+
+use super::class::ClassMember;
+#[cfg(feature = "jsx")]
+use super::jsx::{self, JsxAttr, JsxChild, JsxTag};
+use super::validate::validate_identifier;
+#[cfg(feature = "typescript")]
+use super::typescript::{TsInterfaceMember, TsType};
+use crate::codegen::{display_generated, CodegenError, CodegenOptions, QuoteStyle, SemicolonMode};
+use crate::codegen::sourcemap::{end_position, Mapping, SourceLocation, SourceMapSink};
+
 /// Block of code in a module / function.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Block {
     /// Indentation level of the block.
     pub indent: usize,
     /// The statements in the block.
     pub statements: Vec<Statement>,
+    /// Kind of function the block belongs to, decides if `await` and `yield` can be used.
+    pub context: BlockContext,
+}
+
+/// Kind of function a block belongs to.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BlockContext {
+    /// Regular code, `await` and `yield` can't be used.
+    #[default]
+    Normal,
+    /// Body of an async function, `await` can be used.
+    AsyncFunction,
+    /// Body of an async generator function, both `await` and `yield` can be used.
+    AsyncGenerator,
+    /// Body of a generator function, `yield` can be used.
+    Generator,
+}
+
+impl BlockContext {
+    /// Get the context of the body of a function.
+    pub fn function(is_async: bool, is_generator: bool) -> Self {
+        match (is_async, is_generator) {
+            (false, false) => BlockContext::Normal,
+            (true, false) => BlockContext::AsyncFunction,
+            (true, true) => BlockContext::AsyncGenerator,
+            (false, true) => BlockContext::Generator
+        }
+    }
+
+    /// Check if `await` can be used in the context.
+    pub fn allows_await(&self) -> bool {
+        matches!(self, BlockContext::AsyncFunction | BlockContext::AsyncGenerator)
+    }
+
+    /// Check if `yield` can be used in the context.
+    pub fn allows_yield(&self) -> bool {
+        matches!(self, BlockContext::Generator | BlockContext::AsyncGenerator)
+    }
+
+    /// Check that the statement doesn't use `await` or `yield` where the context doesn't allow it.
+    /// Bodies of class methods are not checked, they belong to their own functions. Field values and
+    /// parameter defaults are checked as normal code, as they never allow `await` or `yield`.
+    pub(crate) fn check(&self, statement: &Statement) -> Result<(), CodegenError> {
+        match statement {
+            Statement::Await(_) if !self.allows_await() => return Err(CodegenError::AwaitOutsideAsync),
+            Statement::Yield { .. } if !self.allows_yield() => return Err(CodegenError::YieldOutsideGenerator),
+            Statement::ClassDecl { superclass, body, .. } => {
+                superclass.iter().try_for_each(|superclass| self.check(superclass))?;
+                return body.iter()
+                    .flat_map(|member| member.initializers())
+                    .try_for_each(|value| BlockContext::Normal.check(value));
+            },
+            _ => {}
+        }
+        statement.children().into_iter().try_for_each(|child| self.check(child))
+    }
 }
 
 /// Statement for a block.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Statement {
     /// Raw line of code.
     Raw(String),
@@ -17,6 +87,25 @@ pub enum Statement {
         /// The value of the literal.
         value: String
     },
+    /// String literal, quoted and escaped according to `CodegenOptions::quote_style`.
+    StringLiteral(String),
+    /// Regular expression literal (eg. `/foo/gi`).
+    RegexLiteral {
+        /// The pattern, unescaped `/` characters are escaped during generation.
+        pattern: String,
+        /// The flags, each one of `d`, `g`, `i`, `m`, `s`, `u`, `v`, `y`.
+        flags: String
+    },
+    /// BigInt literal, holds the digits without the trailing `n` (eg. `42` or `0xFF`).
+    BigIntLiteral(String),
+    /// The `null` value.
+    Null,
+    /// The `undefined` value.
+    Undefined,
+    /// The `NaN` value.
+    NaN,
+    /// The `Infinity` value.
+    Infinity,
     /// Variable declaration.
     VarDecl {
         /// The type of the variable.
@@ -24,7 +113,26 @@ pub enum Statement {
         /// The name of the variable.
         name: String,
         /// Initializer expression.
-        initializer: Option<Box<Statement>>
+        initializer: Option<Box<Statement>>,
+        /// Type of the variable (eg. `let x: number`).
+        #[cfg(feature = "typescript")]
+        type_annotation: Option<TsType>
+    },
+    /// Destructuring variable declaration (eg. `const { a, b } = obj` or `const [x, y] = arr`).
+    DestructureDecl {
+        /// The type of the variables.
+        var_type: VarType,
+        /// The pattern binding the variables.
+        pattern: DestructurePattern,
+        /// The destructured value.
+        initializer: Box<Statement>
+    },
+    /// Variable declaration with multiple declarators (eg. `let a = 1, b = 2, c`).
+    MultiVarDecl {
+        /// The type of the variables.
+        var_type: VarType,
+        /// The names of the variables with their initializers.
+        declarators: Vec<(String, Option<Box<Statement>>)>
     },
     /// Binary expression.
     Binary {
@@ -35,154 +143,3434 @@ pub enum Statement {
         /// The right side of the expression.
         right: Box<Statement>
     },
+    /// Instance check (eg. `x instanceof Date`).
+    Instanceof {
+        /// The checked value.
+        left: Box<Statement>,
+        /// The constructor the value is checked against.
+        right: Box<Statement>
+    },
+    /// Property presence check (eg. `'key' in obj`), `in` is reserved in Rust, hence the name.
+    InExpr {
+        /// The property key.
+        left: Box<Statement>,
+        /// The object the property is looked up in.
+        right: Box<Statement>
+    },
+    /// Guarded expression chain (eg. `a && a.b && a.b()`).
+    ConditionalChain {
+        /// The guards checked before evaluating the final expression.
+        guards: Vec<Statement>,
+        /// The expression evaluated when all guards are truthy.
+        final_expr: Box<Statement>
+    },
+    /// Assignment expression (eg. `foo = 42` or `foo ??= 42`).
+    Assign {
+        /// The name of the assigned variable.
+        name: String,
+        /// The assignment operator (eg. `=`, `+=`, `??=`).
+        operator: String,
+        /// The assigned value.
+        value: Box<Statement>
+    },
+    /// Reference to a variable or other binding by name (eg. `foo`).
+    Identifier(String),
+    /// Property access (eg. `foo.bar`).
+    MemberAccess {
+        /// The accessed object.
+        object: Box<Statement>,
+        /// The name of the accessed property.
+        property: String
+    },
+    /// Function call (eg. `foo(a, b)`).
+    Call {
+        /// The called function.
+        callee: Box<Statement>,
+        /// The arguments of the call.
+        args: Vec<Statement>,
+        /// Type arguments of the call (eg. `identity<string>(x)`).
+        #[cfg(feature = "typescript")]
+        type_args: Vec<TsType>
+    },
+    /// Computed property access (eg. `foo[0]` or `foo['bar']`).
+    Index {
+        /// The accessed object.
+        object: Box<Statement>,
+        /// The accessed property.
+        index: Box<Statement>
+    },
+    /// Template literal (eg. `` `Hello ${name}!` ``).
+    TemplateLiteral {
+        /// The text parts, there is one more than there are expressions.
+        quasis: Vec<String>,
+        /// The interpolated expressions placed between the text parts.
+        expressions: Vec<Statement>
+    },
+    /// Tagged template (eg. ``html`<div>${content}</div>` ``).
+    TaggedTemplate {
+        /// The tag function.
+        tag: Box<Statement>,
+        /// The template passed to the tag, must be a `TemplateLiteral`.
+        template: Box<Statement>
+    },
+    /// Await expression (eg. `await foo()`).
+    Await(Box<Statement>),
+    /// Typeof expression, which doesn't throw for undeclared variables (eg. `typeof foo`).
+    TypeOf(Box<Statement>),
+    /// Void expression (eg. `void 0`).
+    VoidExpr(Box<Statement>),
+    /// Yield expression (eg. `yield foo` or `yield* foo`).
+    Yield {
+        /// The yielded value.
+        value: Option<Box<Statement>>,
+        /// Whether the yield delegates to another generator (`yield*`).
+        delegate: bool
+    },
+    /// Comma operator evaluating all expressions and returning the last (eg. `a, b, c`).
+    /// It has the lowest precedence, so it is parenthesized wherever a single expression is expected (eg. `f((a, b))`).
+    Sequence(Vec<Statement>),
+    /// Expression used as a statement, terminated by itself (eg. `foo();`).
+    ExpressionStatement(Box<Statement>),
+    /// TypeScript `as const` assertion (eg. `({ foo: 1 } as const)`).
+    #[cfg(feature = "typescript")]
+    TsAsConst(Box<Statement>),
+    /// TypeScript type assertion (eg. `foo() as Bar`).
+    #[cfg(feature = "typescript")]
+    TsAssertion {
+        /// The asserted expression.
+        expr: Box<Statement>,
+        /// The type the expression is asserted to.
+        type_ann: TsType
+    },
+    /// TypeScript non-null assertion (eg. `foo!`).
+    #[cfg(feature = "typescript")]
+    TsNonNull(Box<Statement>),
+    /// TypeScript ambient declaration (eg. `declare const x: number`).
+    #[cfg(feature = "typescript")]
+    TsDeclare(Box<Statement>),
+    /// TypeScript ambient module declaration (eg. `declare module 'foo' { ... }`).
+    #[cfg(feature = "typescript")]
+    TsDeclareModule {
+        /// The name of the module.
+        name: String,
+        /// The declarations inside the module.
+        body: Box<Block>
+    },
+    /// TypeScript interface declaration (eg. `interface Foo extends Bar { x: number }`).
+    #[cfg(feature = "typescript")]
+    TsInterface {
+        /// The name of the interface.
+        name: String,
+        /// The interfaces being extended.
+        extends: Vec<String>,
+        /// The members of the interface.
+        members: Vec<TsInterfaceMember>
+    },
+    /// TypeScript type alias declaration (eg. `type Pair<A, B> = [A, B]`).
+    #[cfg(feature = "typescript")]
+    TsTypeAlias {
+        /// The name of the alias.
+        name: String,
+        /// The generic type parameters of the alias.
+        type_params: Vec<String>,
+        /// The aliased type.
+        definition: TsType
+    },
+    /// TypeScript type-only export list (eg. `export type { Foo } from './foo'`).
+    #[cfg(feature = "typescript")]
+    TsExportType {
+        /// The exported types.
+        specifiers: Vec<ExportSpecifier>,
+        /// The path the types are re-exported from.
+        from_path: Option<String>
+    },
+    /// JSX element (eg. `<div className="foo">{bar}</div>`).
+    #[cfg(feature = "jsx")]
+    JsxElement {
+        /// The name of the element.
+        tag: JsxTag,
+        /// The attributes of the element.
+        attributes: Vec<JsxAttr>,
+        /// The children of the element.
+        children: Vec<JsxChild>,
+        /// Whether the element is written as `<tag />`, it must not have children.
+        self_closing: bool
+    },
+    /// JSX fragment (eg. `<>foo</>`).
+    #[cfg(feature = "jsx")]
+    JsxFragment {
+        /// The children of the fragment.
+        children: Vec<JsxChild>
+    },
+    /// Export of a declaration or expression (eg. `export const foo = 42`).
+    Export {
+        /// The exported declaration.
+        declaration: Box<Statement>,
+        /// Whether this is a default export (eg. `export default foo`, see `Statement::export_default`).
+        /// Default exports are terminated with a semicolon unless exporting a class declaration.
+        is_default: bool
+    },
+    /// Export list (eg. `export { foo, bar as baz }`).
+    ExportList {
+        /// The exported names.
+        names: Vec<ExportSpecifier>,
+        /// Module to re-export from (eg. `export { foo } from 'bar'` would be `Some("bar")`).
+        from_path: Option<String>
+    },
+    /// Re-export of everything from a module (eg. `export * from 'foo'`).
+    ExportAll(String),
+    /// CommonJS import (eg. `const { foo, bar } = require('baz')`).
+    Require {
+        /// Name bound to the whole module (eg. `const foo = require('bar')`).
+        binding: Option<String>,
+        /// Destructured names, aliases are written as `foo as bar`.
+        imports: Vec<String>,
+        /// Path of the required module.
+        path: String
+    },
+    /// CommonJS exports of the module (eg. `module.exports = { foo, bar: baz }`).
+    ModuleExports(Vec<ExportSpecifier>),
+    /// The `import.meta` object of the current module.
+    ImportMeta,
+    /// The `debugger` statement.
+    Debugger,
+    /// Labeled statement (eg. `outer: for (...) { break outer }`).
+    Labeled {
+        /// The label, must not be a reserved word.
+        label: String,
+        /// The labeled statement.
+        body: Box<Statement>
+    },
+    /// Class declaration.
+    ClassDecl {
+        /// The name of the class.
+        name: String,
+        /// The class being extended.
+        superclass: Option<Box<Statement>>,
+        /// The members of the class.
+        body: Vec<ClassMember>
+    },
     /// Block of code.
-    Block(Box<Block>)
+    Block(Box<Block>),
+    /// Line comment (eg. `// foo`), each line of the text gets its own `//`.
+    LineComment(String),
+    /// Block comment (eg. `/* foo */`), multi-line text is written in JSDoc style with ` * ` prefixes.
+    BlockComment(String),
+    /// Documentation comment (eg. `/** foo */`), always written over multiple lines with ` * ` prefixes.
+    DocComment(String),
+    /// Statement followed by a line comment on the same line (eg. `let x = 1; // foo`).
+    Commented {
+        /// The commented statement.
+        statement: Box<Statement>,
+        /// The text of the comment.
+        comment: String
+    },
+    /// Statement with a location in an original source, recorded when generating a source map.
+    Mapped {
+        /// The mapped statement.
+        statement: Box<Statement>,
+        /// The original location of the statement.
+        location: SourceLocation
+    }
 }
 
-/// The type of a variable.
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum VarType {
-    Let, Const, Var
+/// Single name in an export list.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExportSpecifier {
+    /// The local name.
+    pub local: String,
+    /// The exported name if different from the local one (eg. `export { foo as bar }`).
+    pub exported: Option<String>,
 }
 
-impl Statement {
-    /// Create js code for the statement.
+impl ExportSpecifier {
+    /// Create a new export specifier.
+    pub fn new(local: &str) -> Self {
+        Self {
+            local: local.to_string(),
+            exported: None,
+        }
+    }
+
+    /// Create a new export specifier exported under a different name.
+    pub fn aliased(local: &str, exported: &str) -> Self {
+        Self {
+            local: local.to_string(),
+            exported: Some(exported.to_string()),
+        }
+    }
+
+    /// Create js code for the export specifier.
     pub fn generate(&self) -> String {
+        match &self.exported {
+            Some(exported) => format!("{} as {}", self.local, exported),
+            None => self.local.clone()
+        }
+    }
+}
+
+/// Pattern of a destructuring declaration.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DestructurePattern {
+    /// Object pattern (eg. `{ a, b: c }`).
+    Object(Vec<ObjectPattern>),
+    /// Array pattern (eg. `[a, , b]`).
+    Array(Vec<ArrayPattern>),
+}
+
+/// Property of an object pattern (eg. `a: renamed = 1` or `...rest`).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ObjectPattern {
+    /// The destructured property, or the name of the rest binding.
+    pub key: String,
+    /// The variable the property is bound to if different from the key (eg. `{ a: renamed }`).
+    pub alias: Option<String>,
+    /// Value used when the property is undefined.
+    pub default: Option<Box<Statement>>,
+    /// Whether the remaining properties are collected (eg. `{ ...rest }`).
+    pub rest: bool,
+}
+
+/// Element of an array pattern (eg. `first = 1` or `...rest`).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ArrayPattern {
+    /// The variable the element is bound to, skipped elements have no binding (eg. `[, second]`).
+    pub binding: Option<String>,
+    /// Value used when the element is undefined.
+    pub default: Option<Box<Statement>>,
+    /// Whether the remaining elements are collected (eg. `[...rest]`).
+    pub rest: bool,
+}
+
+impl DestructurePattern {
+    /// Get the names of all variables bound by the pattern.
+    pub fn bindings(&self) -> Vec<&str> {
         match self {
-            Statement::Raw(code) => code.clone(),
-            Statement::Literal { value } => value.clone(),
-            Statement::VarDecl { var_type, name, initializer } => {
-                let var_type = match var_type {
-                    VarType::Let => "let",
-                    VarType::Const => "const",
-                    VarType::Var => "var"
-                };
-                let initializer = match initializer {
-                    Some(initializer) => format!(" = {}", initializer.generate()),
-                    None => "".to_string()
-                };
-                format!("{} {}{}", var_type, name, initializer)
+            DestructurePattern::Object(properties) => properties.iter()
+                .map(|property| property.alias.as_deref().unwrap_or(&property.key))
+                .collect(),
+            DestructurePattern::Array(elements) => elements.iter()
+                .filter_map(|element| element.binding.as_deref())
+                .collect()
+        }
+    }
+
+    /// Get the default values of the pattern.
+    pub(crate) fn children(&self) -> Vec<&Statement> {
+        match self {
+            DestructurePattern::Object(properties) => properties.iter().filter_map(|property| property.default.as_deref()).collect(),
+            DestructurePattern::Array(elements) => elements.iter().filter_map(|element| element.default.as_deref()).collect()
+        }
+    }
+
+    /// Get the default values of the pattern for modification.
+    pub(crate) fn children_mut(&mut self) -> Vec<&mut Statement> {
+        match self {
+            DestructurePattern::Object(properties) => {
+                properties.iter_mut().filter_map(|property| property.default.as_deref_mut()).collect()
             },
-            Statement::Binary { left, operator, right } => {
-                format!("({} {} {})", left.generate(), operator, right.generate())
-            }
-            Statement::Block(block) => {
-                block.generate()
-            }
+            DestructurePattern::Array(elements) => elements.iter_mut().filter_map(|element| element.default.as_deref_mut()).collect()
         }
     }
 
-    pub fn boxed(self) -> Box<Self> {
-        Box::new(self)
+    /// Create js code for the pattern, fails if a binding is not a valid identifier
+    /// or a rest element is not the last one or has a default value.
+    pub fn generate_indented(&self, indent: usize, options: &CodegenOptions) -> Result<String, CodegenError> {
+        let separator = format!(",{}", options.space());
+        let code = match self {
+            DestructurePattern::Object(properties) => {
+                check_rest(properties.iter().map(|property| (property.rest, property.default.is_some() || property.alias.is_some())))?;
+                let properties = properties.iter()
+                    .map(|property| {
+                        let binding = property.alias.as_ref().unwrap_or(&property.key);
+                        validate_identifier(binding)?;
+                        let mut code = match (&property.alias, property.rest) {
+                            (_, true) => format!("...{}", property.key),
+                            (Some(alias), false) => format!("{}:{}{}", property.key, options.space(), alias),
+                            (None, false) => property.key.clone()
+                        };
+                        code.push_str(&generate_default(&property.default, indent, options)?);
+                        Ok(code)
+                    })
+                    .collect::<Result<Vec<_>, CodegenError>>()?;
+                if properties.is_empty() {
+                    "{}".to_string()
+                } else {
+                    format!("{{{}{}{}}}", options.space(), properties.join(&separator), options.space())
+                }
+            },
+            DestructurePattern::Array(elements) => {
+                check_rest(elements.iter().map(|element| (element.rest, element.default.is_some() || element.binding.is_none())))?;
+                let mut elements = elements.iter()
+                    .map(|element| {
+                        let Some(binding) = &element.binding else {
+                            return Ok(String::new());
+                        };
+                        validate_identifier(binding)?;
+                        let rest = if element.rest { "..." } else { "" };
+                        Ok(format!("{}{}{}", rest, binding, generate_default(&element.default, indent, options)?))
+                    })
+                    .collect::<Result<Vec<_>, CodegenError>>()?;
+                // A trailing hole needs its own comma to be kept (eg. `[a, ,]`).
+                if elements.last().is_some_and(String::is_empty) {
+                    elements.push(String::new());
+                }
+                format!("[{}]", elements.join(&separator).trim_end())
+            }
+        };
+        Ok(code)
     }
 }
 
-impl Block {
-    /// Create a new block.
-    pub fn new(indent: usize) -> Self {
+impl ObjectPattern {
+    /// Create a new property bound to a variable with the same name.
+    pub fn new(key: &str) -> Self {
         Self {
-            indent,
-            statements: Vec::new(),
+            key: key.to_string(),
+            alias: None,
+            default: None,
+            rest: false,
         }
     }
 
-    /// Add a statement to the block.
-    pub fn stmt(&mut self, statement: Statement) -> &mut Self {
-        self.statements.push(statement);
+    /// Create a new property bound to a variable with a different name.
+    pub fn aliased(key: &str, alias: &str) -> Self {
+        Self {
+            alias: Some(alias.to_string()),
+            ..Self::new(key)
+        }
+    }
+
+    /// Create a new rest property collecting the remaining properties.
+    pub fn rest(name: &str) -> Self {
+        Self {
+            rest: true,
+            ..Self::new(name)
+        }
+    }
+
+    /// Set the value used when the property is undefined.
+    pub fn with_default(mut self, default: impl Into<Statement>) -> Self {
+        self.default = Some(Box::new(default.into()));
         self
     }
+}
 
-    /// Add raw code to the block.
-    pub fn raw(&mut self, code: &str) -> &mut Self {
-        self.stmt(Statement::Raw(code.to_string()))
+impl ArrayPattern {
+    /// Create a new element bound to a variable.
+    pub fn new(binding: &str) -> Self {
+        Self {
+            binding: Some(binding.to_string()),
+            default: None,
+            rest: false,
+        }
     }
 
-    /// Add a variable declaration to the block.
-    pub fn var_decl(&mut self, var_type: VarType, name: &str, initializer: Option<Statement>) -> &mut Self {
-        self.stmt(Statement::VarDecl {
-            var_type,
-            name: name.to_string(),
-            initializer: match initializer {
-                Some(initializer) => Some(initializer.into()),
-                None => None
-            }
-        })
+    /// Create a new skipped element.
+    pub fn hole() -> Self {
+        Self {
+            binding: None,
+            default: None,
+            rest: false,
+        }
     }
 
-    /// Add a literal to the block.
-    pub fn literal(&mut self, value: impl Into<Statement>) -> &mut Self {
-        let value = value.into();
-        if let Statement::Literal { value } = value {
-            self.stmt(Statement::Literal { value })
-        } else {
-            panic!("Expected literal statement")
+    /// Create a new rest element collecting the remaining elements.
+    pub fn rest(name: &str) -> Self {
+        Self {
+            rest: true,
+            ..Self::new(name)
         }
     }
 
-    /// Add a binary expression to the block.
-    pub fn binary(&mut self, left: impl Into<Statement>, operator: &str, right: impl Into<Statement>) -> &mut Self {
-        self.stmt(Statement::Binary {
-            left: Box::new(left.into()),
-            operator: operator.to_string(),
-            right: Box::new(right.into())
-        })
+    /// Set the value used when the element is undefined.
+    pub fn with_default(mut self, default: impl Into<Statement>) -> Self {
+        self.default = Some(Box::new(default.into()));
+        self
     }
+}
 
-    /// Generate the block's code.
-    pub fn generate(&self) -> String {
-        let mut code = String::new();
+/// The type of a variable.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VarType {
+    Let, Const, Var
+}
 
-        for statement in &self.statements {
-            code.push_str(&format!("{}{}\n", "    ".repeat(self.indent), statement.generate()));
+impl VarType {
+    /// Get the keyword of the variable type.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VarType::Let => "let",
+            VarType::Const => "const",
+            VarType::Var => "var"
         }
-
-        code
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::module::block::{Block, Statement, VarType};
+impl Statement {
+    /// Create js code for the statement.
+    pub fn generate(&self) -> Result<String, CodegenError> {
+        self.generate_with_options(&CodegenOptions::default())
+    }
 
-    #[test]
-    fn test_raw_stmt() {
-        let mut block = Block::new(0);
-        block.stmt(Statement::Raw("foo".to_string()));
-        assert_eq!(block.generate(), "foo\n");
+    /// Create js code for the statement using the given options.
+    pub fn generate_with_options(&self, options: &CodegenOptions) -> Result<String, CodegenError> {
+        self.generate_indented(0, options)
     }
 
-    #[test]
-    fn test_var_decl_stmt() {
-        let mut block = Block::new(0);
-        block.stmt(Statement::VarDecl {
-            var_type: VarType::Let,
-            name: "foo".to_string(),
-            initializer: None
-        });
-        assert_eq!(block.generate(), "let foo\n");
+    /// Create js code for the statement, alias of `generate`.
+    pub fn to_js_string(&self) -> Result<String, CodegenError> {
+        self.generate()
     }
-}
 
-impl From<&str> for Statement {
-    fn from(code: &str) -> Self {
-        Statement::Literal { value: format!("'{}'", code) }
+    /// Create js code for the statement using the given options, alias of `generate_with_options`.
+    pub fn to_js_string_with_options(&self, options: &CodegenOptions) -> Result<String, CodegenError> {
+        self.generate_with_options(options)
     }
-}
 
-impl From<String> for Statement {
-    fn from(code: String) -> Self {
-        Statement::Literal { value: format!("'{}'", code) }
+    /// Create js code for the statement at the given indentation level.
+    /// Multi-line statements (eg. classes) indent their inner lines relative to `indent`,
+    /// the first line is never indented.
+    pub fn generate_indented(&self, indent: usize, options: &CodegenOptions) -> Result<String, CodegenError> {
+        let code = match self {
+            Statement::Raw(code) => code.clone(),
+            Statement::Literal { value } => value.clone(),
+            Statement::StringLiteral(value) => options.quote_style.quote(value),
+            Statement::RegexLiteral { pattern, flags } => {
+                for (i, flag) in flags.char_indices() {
+                    if !REGEX_FLAGS.contains(flag) {
+                        return Err(CodegenError::InvalidStatement {
+                            context: format!("Invalid regex flag `{}` in `{}`", flag, flags)
+                        });
+                    }
+                    if flags[..i].contains(flag) {
+                        return Err(CodegenError::InvalidStatement {
+                            context: format!("Duplicate regex flag `{}` in `{}`", flag, flags)
+                        });
+                    }
+                }
+                format!("/{}/{}", escape_regex_pattern(pattern), flags)
+            },
+            Statement::BigIntLiteral(value) => format!("{}n", value),
+            Statement::Null => "null".to_string(),
+            Statement::Undefined => "undefined".to_string(),
+            Statement::NaN => "NaN".to_string(),
+            Statement::Infinity => "Infinity".to_string(),
+            Statement::VarDecl { var_type, name, initializer, .. } => {
+                // Declarations only keep the initializers of constants with literal values.
+                let kept = initializer.as_ref().filter(|initializer| {
+                    !options.is_declaration() || (*var_type == VarType::Const && initializer.is_literal())
+                });
+                let mut type_name = None;
+                #[cfg(feature = "typescript")]
+                if let Statement::VarDecl { type_annotation: Some(type_annotation), .. } = self {
+                    type_name = Some(type_annotation.generate_with_options(options)?);
+                }
+                if let (Some(initializer), None) = (initializer, kept) {
+                    type_name.get_or_insert_with(|| initializer.declared_type().to_string());
+                }
+                let var_type = var_type.as_str();
+                let name = match type_name {
+                    Some(type_name) => format!("{}:{}{}", name, options.space(), type_name),
+                    None => name.clone()
+                };
+                match kept {
+                    Some(initializer) => {
+                        let initializer = initializer.generate_parenthesized(initializer.precedence() < ASSIGNMENT_PRECEDENCE, indent, options)?;
+                        options.join_tokens(&[var_type, &name, "=", &initializer])
+                    },
+                    None => format!("{} {}", var_type, name)
+                }
+            },
+            Statement::DestructureDecl { var_type, pattern, initializer } => {
+                let pattern = pattern.generate_indented(indent, options)?;
+                let initializer = initializer.generate_parenthesized(initializer.precedence() < ASSIGNMENT_PRECEDENCE, indent, options)?;
+                options.join_tokens(&[var_type.as_str(), &pattern, "=", &initializer])
+            },
+            Statement::MultiVarDecl { var_type, declarators } => {
+                if declarators.is_empty() {
+                    return Err(CodegenError::InvalidStatement { context: "Declaration must have at least one declarator".to_string() });
+                }
+                let declarators = declarators.iter()
+                    .map(|(name, initializer)| {
+                        let kept = initializer.as_ref().filter(|initializer| {
+                            !options.is_declaration() || (*var_type == VarType::Const && initializer.is_literal())
+                        });
+                        match (initializer, kept) {
+                            (_, Some(initializer)) => {
+                                let parens = initializer.precedence() < ASSIGNMENT_PRECEDENCE;
+                                Ok(options.join_tokens(&[name, "=", &initializer.generate_parenthesized(parens, indent, options)?]))
+                            },
+                            (Some(initializer), None) => Ok(format!("{}:{}{}", name, options.space(), initializer.declared_type())),
+                            (None, None) => Ok(name.clone())
+                        }
+                    })
+                    .collect::<Result<Vec<_>, CodegenError>>()?;
+                format!("{} {}", var_type.as_str(), declarators.join(&format!(",{}", options.space())))
+            },
+            Statement::Binary { left, operator, right } => {
+                if !BINARY_OPERATORS.contains(&operator.as_str()) {
+                    return Err(CodegenError::InvalidOperator(operator.clone()));
+                }
+                generate_binary(left, operator, right, indent, options)?
+            },
+            Statement::Instanceof { left, right } => generate_binary(left, "instanceof", right, indent, options)?,
+            Statement::InExpr { left, right } => generate_binary(left, "in", right, indent, options)?,
+            Statement::ConditionalChain { guards, final_expr } => {
+                guards.iter()
+                    .chain(std::iter::once(final_expr.as_ref()))
+                    .map(|expr| {
+                        let parens = expr.precedence() < binary_precedence("&&") || mixes_nullish("&&", expr);
+                        expr.generate_parenthesized(parens, indent, options)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?
+                    .join(&format!("{}&&{}", options.space(), options.space()))
+            },
+            Statement::Assign { name, operator, value } => {
+                if !ASSIGNMENT_OPERATORS.contains(&operator.as_str()) {
+                    return Err(CodegenError::InvalidOperator(operator.clone()));
+                }
+                let value = value.generate_parenthesized(value.precedence() < ASSIGNMENT_PRECEDENCE, indent, options)?;
+                options.join_tokens(&[name, operator, &value])
+            },
+            Statement::Identifier(name) => {
+                validate_identifier(name)?;
+                name.clone()
+            },
+            Statement::MemberAccess { object, property } => {
+                // Integer literals would read the dot as a decimal point (eg. `(1).toString`).
+                let parens = object.precedence() < MEMBER_PRECEDENCE
+                    || matches!(&**object, Statement::Literal { value } if value.chars().all(|c| c.is_ascii_digit()));
+                format!("{}.{}", object.generate_parenthesized(parens, indent, options)?, property)
+            },
+            Statement::Call { callee, args, .. } => {
+                #[allow(unused_mut)]
+                let mut callee = callee.generate_parenthesized(callee.precedence() < MEMBER_PRECEDENCE, indent, options)?;
+                #[cfg(feature = "typescript")]
+                if let Statement::Call { type_args, .. } = self {
+                    if !type_args.is_empty() {
+                        let type_args = type_args.iter()
+                            .map(|type_arg| type_arg.generate_with_options(options))
+                            .collect::<Result<Vec<_>, _>>()?;
+                        callee = format!("{}<{}>", callee, type_args.join(&format!(",{}", options.space())));
+                    }
+                }
+                let generate_args = |indent: usize| args.iter()
+                    .map(|arg| arg.generate_parenthesized(arg.precedence() < ASSIGNMENT_PRECEDENCE, indent, options))
+                    .collect::<Result<Vec<_>, _>>();
+                let code = format!("{}({})", callee, generate_args(indent)?.join(&format!(",{}", options.space())));
+                if args.is_empty() || !options.exceeds_line_width(indent, &code) {
+                    code
+                } else {
+                    // Too long for one line, put each argument on its own line.
+                    let arg_indent = options.indent(indent + 1);
+                    let args = generate_args(indent + 1)?.iter()
+                        .map(|arg| format!("{}{}", arg_indent, arg))
+                        .collect::<Vec<_>>()
+                        .join(",\n");
+                    format!("{}(\n{}\n{})", callee, args, options.indent(indent))
+                }
+            },
+            Statement::TemplateLiteral { quasis, expressions } => {
+                if quasis.len() != expressions.len() + 1 {
+                    return Err(CodegenError::InvalidStatement {
+                        context: format!("Template literal with {} expressions must have {} text parts", expressions.len(), expressions.len() + 1)
+                    });
+                }
+                let mut code = format!("`{}", escape_template(&quasis[0]));
+                for (expression, quasi) in expressions.iter().zip(&quasis[1..]) {
+                    expression.check_expression()?;
+                    code.push_str(&format!("${{{}}}{}", expression.generate_indented(indent, options)?, escape_template(quasi)));
+                }
+                code.push('`');
+                code
+            },
+            Statement::TaggedTemplate { tag, template } => {
+                if !matches!(**template, Statement::TemplateLiteral { .. }) {
+                    return Err(CodegenError::InvalidStatement {
+                        context: format!("Expected template literal, got {:?}", template)
+                    });
+                }
+                let tag = tag.generate_parenthesized(tag.precedence() < MEMBER_PRECEDENCE, indent, options)?;
+                format!("{}{}", tag, template.generate_indented(indent, options)?)
+            },
+            Statement::Index { object, index } => {
+                let object = object.generate_parenthesized(object.precedence() < MEMBER_PRECEDENCE, indent, options)?;
+                index.check_expression()?;
+                format!("{}[{}]", object, index.generate_indented(indent, options)?)
+            },
+            Statement::Await(value) => {
+                format!("await {}", value.generate_parenthesized(value.precedence() < PREFIX_PRECEDENCE, indent, options)?)
+            },
+            Statement::TypeOf(value) => {
+                format!("typeof {}", value.generate_parenthesized(value.precedence() < PREFIX_PRECEDENCE, indent, options)?)
+            },
+            Statement::VoidExpr(value) => {
+                format!("void {}", value.generate_parenthesized(value.precedence() < PREFIX_PRECEDENCE, indent, options)?)
+            },
+            Statement::Yield { value, delegate } => {
+                let keyword = if *delegate { "yield*" } else { "yield" };
+                match value {
+                    Some(value) => {
+                        let value = value.generate_parenthesized(value.precedence() < ASSIGNMENT_PRECEDENCE, indent, options)?;
+                        format!("{} {}", keyword, value)
+                    },
+                    None => keyword.to_string()
+                }
+            },
+            Statement::Sequence(expressions) => {
+                if expressions.is_empty() {
+                    return Err(CodegenError::InvalidStatement { context: "Sequence must have at least one expression".to_string() });
+                }
+                let expressions = expressions.iter()
+                    .map(|expr| expr.generate_parenthesized(expr.precedence() < ASSIGNMENT_PRECEDENCE, indent, options))
+                    .collect::<Result<Vec<_>, _>>()?;
+                expressions.join(&format!(",{}", options.space()))
+            },
+            Statement::ExpressionStatement(expr) => {
+                expr.check_expression()?;
+                // A class at the start of a statement would be a declaration.
+                let parens = matches!(**expr, Statement::ClassDecl { .. });
+                let code = expr.generate_parenthesized(parens, indent, options)?;
+                // Without semicolons, these characters would continue the previous statement (eg. `x = 5\n(foo)` is a call).
+                let terminator = options.member_terminator();
+                let guard = if terminator.is_empty() && code.starts_with(['(', '[', '`', '+', '-', '/']) { ";" } else { "" };
+                format!("{}{}{}", guard, code, terminator)
+            },
+            #[cfg(feature = "typescript")]
+            Statement::TsAsConst(value) => {
+                let parens = value.is_expression() && value.precedence() < binary_precedence("as");
+                format!("({} as const)", value.generate_parenthesized(parens, indent, options)?)
+            },
+            #[cfg(feature = "typescript")]
+            Statement::TsAssertion { expr, type_ann } => {
+                let expr = expr.generate_parenthesized(expr.precedence() < binary_precedence("as"), indent, options)?;
+                options.join_tokens(&[&expr, "as", &type_ann.generate_with_options(options)?])
+            },
+            #[cfg(feature = "typescript")]
+            Statement::TsNonNull(expr) => {
+                format!("{}!", expr.generate_parenthesized(expr.precedence() < MEMBER_PRECEDENCE, indent, options)?)
+            },
+            #[cfg(feature = "typescript")]
+            Statement::TsDeclare(declaration) => {
+                if !declaration.is_declaration() && !matches!(**declaration, Statement::Raw(_)) {
+                    return Err(CodegenError::InvalidStatement {
+                        context: format!("Expected declaration after `declare`, got {:?}", declaration)
+                    });
+                }
+                format!("declare {}", declaration.generate_indented(indent, options)?)
+            },
+            #[cfg(feature = "typescript")]
+            Statement::TsDeclareModule { name, body } => {
+                let name = options.quote_style.quote_literal(name);
+                if body.statements.is_empty() {
+                    return Ok(options.join_tokens(&["declare", "module", &name, "{}"]));
+                }
+                format!(
+                    "{}{}{}{}}}",
+                    options.join_tokens(&["declare", "module", &name, "{"]),
+                    options.newline(),
+                    body.generate_indented(indent + 1, options)?,
+                    options.indent(indent)
+                )
+            },
+            #[cfg(feature = "typescript")]
+            Statement::TsInterface { name, extends, members } => {
+                let extends = match extends.is_empty() {
+                    true => "".to_string(),
+                    false => format!("extends {}", extends.join(&format!(",{}", options.space())))
+                };
+                if members.is_empty() {
+                    return Ok(options.join_tokens(&["interface", name, &extends, "{}"]));
+                }
+
+                let prefix = options.indent(indent + 1);
+                let mut body = String::new();
+                for member in members {
+                    body.push_str(&format!("{}{}{}", prefix, member.generate_with_options(options)?, options.newline()));
+                }
+                format!(
+                    "{}{}{}{}}}",
+                    options.join_tokens(&["interface", name, &extends, "{"]),
+                    options.newline(),
+                    body,
+                    options.indent(indent)
+                )
+            },
+            #[cfg(feature = "typescript")]
+            Statement::TsTypeAlias { name, type_params, definition } => {
+                let name = match type_params.is_empty() {
+                    true => name.clone(),
+                    false => format!("{}<{}>", name, type_params.join(&format!(",{}", options.space())))
+                };
+                options.join_tokens(&["type", &name, "=", &definition.generate_with_options(options)?])
+            },
+            #[cfg(feature = "typescript")]
+            Statement::TsExportType { specifiers, from_path } => {
+                let names = specifiers.iter().map(|name| name.generate()).collect::<Vec<_>>().join(&format!(",{}", options.space()));
+                match from_path {
+                    Some(path) => options.join_tokens(&["export", "type", "{", &names, "}", "from", &options.quote_style.quote_literal(path)]),
+                    None => options.join_tokens(&["export", "type", "{", &names, "}"])
+                }
+            },
+            #[cfg(feature = "jsx")]
+            Statement::JsxElement { tag, attributes, children, self_closing } => {
+                jsx::generate_element(tag, attributes, children, *self_closing, indent, options)?
+            },
+            #[cfg(feature = "jsx")]
+            Statement::JsxFragment { children } => jsx::generate_fragment(children, indent, options)?,
+            Statement::Export { declaration, is_default } => {
+                if *is_default {
+                    // Default exports take a single expression, a sequence would be cut at its first comma.
+                    let parens = declaration.is_expression() && declaration.precedence() < ASSIGNMENT_PRECEDENCE;
+                    format!("export default {}", declaration.generate_parenthesized(parens, indent, options)?)
+                } else {
+                    if !declaration.is_declaration() && !matches!(**declaration, Statement::Raw(_)) {
+                        return Err(CodegenError::InvalidStatement {
+                            context: format!("Expected declaration after `export`, got {:?}", declaration)
+                        });
+                    }
+                    format!("export {}", declaration.generate_indented(indent, options)?)
+                }
+            },
+            Statement::ExportList { names, from_path } => {
+                let names = names.iter().map(|name| name.generate()).collect::<Vec<_>>().join(&format!(",{}", options.space()));
+                match from_path {
+                    Some(path) => options.join_tokens(&["export", "{", &names, "}", "from", &options.quote_style.quote_literal(path)]),
+                    None => options.join_tokens(&["export", "{", &names, "}"])
+                }
+            },
+            Statement::ExportAll(path) => options.join_tokens(&["export", "*", "from", &options.quote_style.quote_literal(path)]),
+            Statement::Require { binding, imports, path } => {
+                let require = format!("require({})", options.quote_style.quote_literal(path));
+                let separator = format!(",{}", options.space());
+                let imports = imports.iter()
+                    .map(|import| match import.split_once(" as ") {
+                        Some((name, alias)) => format!("{}:{}{}", name.trim(), options.space(), alias.trim()),
+                        None => import.clone()
+                    })
+                    .collect::<Vec<_>>()
+                    .join(&separator);
+                let destructured = options.join_tokens(&["{", &imports, "}"]);
+                match binding {
+                    // The destructured names are taken from the binding instead of requiring the module twice.
+                    Some(binding) if !imports.is_empty() => format!(
+                        "{}{}{}",
+                        options.join_tokens(&["const", binding, "=", &require]),
+                        separator,
+                        options.join_tokens(&[&destructured, "=", binding])
+                    ),
+                    Some(binding) => options.join_tokens(&["const", binding, "=", &require]),
+                    None if !imports.is_empty() => options.join_tokens(&["const", &destructured, "=", &require]),
+                    None => require
+                }
+            },
+            Statement::ModuleExports(names) => {
+                let names = names.iter()
+                    .map(|name| match &name.exported {
+                        Some(exported) => format!("{}:{}{}", exported, options.space(), name.local),
+                        None => name.local.clone()
+                    })
+                    .collect::<Vec<_>>()
+                    .join(&format!(",{}", options.space()));
+                let object = if names.is_empty() { "{}".to_string() } else { options.join_tokens(&["{", &names, "}"]) };
+                options.join_tokens(&["module.exports", "=", &object])
+            },
+            Statement::ImportMeta => "import.meta".to_string(),
+            Statement::Debugger => "debugger".to_string(),
+            Statement::Labeled { label, body } => {
+                validate_identifier(label)?;
+                format!("{}:{}{}{}", label, options.newline(), options.indent(indent + 1), body.generate_indented(indent + 1, options)?)
+            },
+            Statement::ClassDecl { name, superclass, body } => {
+                let superclass = match superclass {
+                    Some(superclass) => {
+                        let parens = superclass.is_expression() && superclass.precedence() < MEMBER_PRECEDENCE;
+                        format!("extends {}", superclass.generate_parenthesized(parens, indent, options)?)
+                    },
+                    None => "".to_string()
+                };
+                // Private members are not part of the declared type.
+                let body = body.iter()
+                    .filter(|member| !options.is_declaration() || !member.is_private())
+                    .collect::<Vec<_>>();
+                if body.is_empty() {
+                    return Ok(options.join_tokens(&["class", name, &superclass, "{}"]));
+                }
+
+                let prefix = options.indent(indent + 1);
+                let mut members = String::new();
+                for member in body {
+                    members.push_str(&format!("{}{}{}", prefix, member.generate_indented(indent + 1, options)?, options.newline()));
+                }
+                format!(
+                    "{}{}{}{}}}",
+                    options.join_tokens(&["class", name, &superclass, "{"]),
+                    options.newline(),
+                    members,
+                    options.indent(indent)
+                )
+            },
+            Statement::Block(block) => {
+                block.generate_with_options(options)?
+            },
+            // Comments are stripped from minified code.
+            Statement::LineComment(_) | Statement::BlockComment(_) | Statement::DocComment(_) if options.minify => "".to_string(),
+            Statement::LineComment(text) => {
+                text.lines()
+                    .map(|line| format!("// {}", line))
+                    .collect::<Vec<_>>()
+                    .join(&format!("\n{}", options.indent(indent)))
+            },
+            Statement::BlockComment(text) => {
+                let text = text.replace("*/", "*\\/");
+                if !text.contains('\n') {
+                    return Ok(format!("/* {} */", text));
+                }
+                let prefix = options.indent(indent);
+                format!("/*\n{}{} */", comment_lines(&text, &prefix), prefix)
+            },
+            Statement::DocComment(text) => {
+                let prefix = options.indent(indent);
+                format!("/**\n{}{} */", comment_lines(&text.replace("*/", "*\\/"), &prefix), prefix)
+            },
+            Statement::Commented { statement, comment } => {
+                let code = format!("{}{}", statement.generate_indented(indent, options)?, statement.terminator(options));
+                if options.minify {
+                    return Ok(code);
+                }
+                format!("{} {}", code, Statement::LineComment(comment.clone()).generate_indented(indent, options)?)
+            },
+            Statement::Mapped { statement, .. } => statement.generate_indented(indent, options)?
+        };
+        Ok(code)
     }
-}
 
-impl From<i32> for Statement {
-    fn from(code: i32) -> Self {
-        Statement::Literal { value: code.to_string() }
+    /// Create js code for the statement, recording the positions of mapped statements in the sink.
+    pub fn generate_with_source_map(&self, options: &CodegenOptions, sink: &mut dyn SourceMapSink) -> Result<String, CodegenError> {
+        self.generate_mapped(0, options, (0, 0), sink)
     }
-}
 
-impl From<f32> for Statement {
-    fn from(code: f32) -> Self {
-        Statement::Literal { value: code.to_string() }
+    /// Create js code for the statement starting at the given generated position (line and column).
+    /// Mappings are recorded for the statement itself and statements of nested blocks.
+    fn generate_mapped(
+        &self,
+        indent: usize,
+        options: &CodegenOptions,
+        position: (u32, u32),
+        sink: &mut dyn SourceMapSink
+    ) -> Result<String, CodegenError> {
+        match self {
+            Statement::Mapped { statement, location } => {
+                sink.add_mapping(Mapping::new(position.0, position.1, location));
+                statement.generate_mapped(indent, options, position, sink)
+            },
+            Statement::Block(block) => block.generate_mapped(block.indent, options, position, sink),
+            _ => self.generate_indented(indent, options)
+        }
+    }
+
+    /// Generate the statement as an operand, wrapping it in parentheses if `parens` is true.
+    pub(crate) fn generate_parenthesized(&self, parens: bool, indent: usize, options: &CodegenOptions) -> Result<String, CodegenError> {
+        self.check_expression()?;
+        let code = self.generate_indented(indent, options)?;
+        Ok(if parens { format!("({})", code) } else { code })
+    }
+
+    /// Call the visitor on the statement and then recursively on all of its child statements.
+    pub fn walk<'a, F: FnMut(&'a Statement)>(&'a self, mut visitor: F) {
+        self.walk_with(&mut visitor);
+    }
+
+    /// Call the visitor on the statement and then recursively on all of its child statements,
+    /// children are visited after the visitor has modified their parent.
+    pub fn walk_mut<F: FnMut(&mut Statement)>(&mut self, mut visitor: F) {
+        self.walk_mut_with(&mut visitor);
+    }
+
+    /// Get the names declared by the statement in its own scope (eg. `foo` for `const foo = bar`).
+    /// Nested blocks are not searched, use `Block::find_var_decls` for that.
+    pub fn collect_declarations(&self) -> Vec<&str> {
+        match self {
+            Statement::ClassDecl { name, .. } => vec![name],
+            Statement::Require { binding, imports, .. } => binding.iter().map(String::as_str)
+                .chain(imports.iter().map(|import| import.rsplit(" as ").next().unwrap_or(import)))
+                .collect(),
+            Statement::Export { declaration: statement, .. } | Statement::Commented { statement, .. }
+                | Statement::Mapped { statement, .. } => statement.collect_declarations(),
+            _ => scope_var_decls(self).into_iter().map(|(_, name)| name).collect()
+        }
     }
-}
\ No newline at end of file
+
+    /// Get the names referenced by the statement and all of its child statements (eg. `bar` and `baz` for `foo(bar, baz)`).
+    /// Each name is listed once, names inside raw code are not found.
+    pub fn collect_identifiers(&self) -> Vec<&str> {
+        let mut identifiers = Vec::new();
+        self.walk(|statement| {
+            let names: Vec<&str> = match statement {
+                Statement::Identifier(name) | Statement::Assign { name, .. } => vec![name],
+                Statement::ExportList { names, from_path: None } | Statement::ModuleExports(names) => {
+                    names.iter().map(|specifier| specifier.local.as_str()).collect()
+                },
+                #[cfg(feature = "jsx")]
+                Statement::JsxElement { tag: JsxTag::Ident(name), .. }
+                    if name.starts_with(|c: char| c.is_ascii_uppercase()) => vec![name],
+                _ => Vec::new()
+            };
+            for name in names {
+                if !identifiers.contains(&name) {
+                    identifiers.push(name);
+                }
+            }
+        });
+        identifiers
+    }
+
+    fn walk_with<'a>(&'a self, visitor: &mut impl FnMut(&'a Statement)) {
+        visitor(self);
+        for child in self.children() {
+            child.walk_with(visitor);
+        }
+    }
+
+    fn walk_mut_with(&mut self, visitor: &mut impl FnMut(&mut Statement)) {
+        visitor(self);
+        for child in self.children_mut() {
+            child.walk_mut_with(visitor);
+        }
+    }
+
+    /// Get the direct child statements of the statement.
+    fn children(&self) -> Vec<&Statement> {
+        match self {
+            Statement::VarDecl { initializer, .. } => initializer.iter().map(|child| &**child).collect(),
+            Statement::MultiVarDecl { declarators, .. } => declarators.iter().filter_map(|(_, child)| child.as_deref()).collect(),
+            Statement::DestructureDecl { pattern, initializer, .. } => {
+                pattern.children().into_iter().chain(std::iter::once(&**initializer)).collect()
+            },
+            Statement::Binary { left, right, .. } | Statement::Instanceof { left, right } | Statement::InExpr { left, right } => {
+                vec![left, right]
+            },
+            Statement::Index { object, index } => vec![object, index],
+            Statement::MemberAccess { object, .. } => vec![object],
+            Statement::Call { callee, args, .. } => std::iter::once(&**callee).chain(args).collect(),
+            Statement::TemplateLiteral { expressions, .. } => expressions.iter().collect(),
+            Statement::TaggedTemplate { tag, template } => vec![tag, template],
+            Statement::ConditionalChain { guards, final_expr } => guards.iter().chain(std::iter::once(&**final_expr)).collect(),
+            Statement::Assign { value, .. } | Statement::Await(value) | Statement::TypeOf(value) | Statement::VoidExpr(value)
+                | Statement::ExpressionStatement(value) => vec![value],
+            Statement::Yield { value, .. } => value.iter().map(|child| &**child).collect(),
+            Statement::Sequence(expressions) => expressions.iter().collect(),
+            #[cfg(feature = "typescript")]
+            Statement::TsAsConst(value) | Statement::TsAssertion { expr: value, .. } | Statement::TsNonNull(value)
+                | Statement::TsDeclare(value) => vec![value],
+            #[cfg(feature = "typescript")]
+            Statement::TsDeclareModule { body, .. } => body.statements.iter().collect(),
+            #[cfg(feature = "jsx")]
+            Statement::JsxElement { tag, attributes, children, .. } => {
+                let tag = match tag {
+                    JsxTag::Member(member) => Some(&**member),
+                    JsxTag::Ident(_) => None
+                };
+                tag.into_iter()
+                    .chain(attributes.iter().flat_map(|attribute| attribute.children()))
+                    .chain(children.iter().flat_map(|child| child.children()))
+                    .collect()
+            },
+            #[cfg(feature = "jsx")]
+            Statement::JsxFragment { children } => children.iter().flat_map(|child| child.children()).collect(),
+            Statement::Export { declaration, .. } => vec![declaration],
+            Statement::Labeled { body, .. } => vec![body],
+            Statement::Commented { statement, .. } | Statement::Mapped { statement, .. } => vec![statement],
+            Statement::ClassDecl { superclass, body, .. } => {
+                superclass.iter().map(|child| &**child)
+                    .chain(body.iter().flat_map(|member| member.children()))
+                    .collect()
+            },
+            Statement::Block(block) => block.statements.iter().collect(),
+            _ => Vec::new()
+        }
+    }
+
+    /// Get the direct child statements of the statement for modification.
+    pub(crate) fn children_mut(&mut self) -> Vec<&mut Statement> {
+        match self {
+            Statement::VarDecl { initializer, .. } => initializer.iter_mut().map(|child| &mut **child).collect(),
+            Statement::MultiVarDecl { declarators, .. } => {
+                declarators.iter_mut().filter_map(|(_, child)| child.as_deref_mut()).collect()
+            },
+            Statement::DestructureDecl { pattern, initializer, .. } => {
+                pattern.children_mut().into_iter().chain(std::iter::once(&mut **initializer)).collect()
+            },
+            Statement::Binary { left, right, .. } | Statement::Instanceof { left, right } | Statement::InExpr { left, right } => {
+                vec![left, right]
+            },
+            Statement::Index { object, index } => vec![object, index],
+            Statement::MemberAccess { object, .. } => vec![object],
+            Statement::Call { callee, args, .. } => std::iter::once(&mut **callee).chain(args).collect(),
+            Statement::TemplateLiteral { expressions, .. } => expressions.iter_mut().collect(),
+            Statement::TaggedTemplate { tag, template } => vec![tag, template],
+            Statement::ConditionalChain { guards, final_expr } => guards.iter_mut().chain(std::iter::once(&mut **final_expr)).collect(),
+            Statement::Assign { value, .. } | Statement::Await(value) | Statement::TypeOf(value) | Statement::VoidExpr(value)
+                | Statement::ExpressionStatement(value) => vec![value],
+            Statement::Yield { value, .. } => value.iter_mut().map(|child| &mut **child).collect(),
+            Statement::Sequence(expressions) => expressions.iter_mut().collect(),
+            #[cfg(feature = "typescript")]
+            Statement::TsAsConst(value) | Statement::TsAssertion { expr: value, .. } | Statement::TsNonNull(value)
+                | Statement::TsDeclare(value) => vec![value],
+            #[cfg(feature = "typescript")]
+            Statement::TsDeclareModule { body, .. } => body.statements.iter_mut().collect(),
+            #[cfg(feature = "jsx")]
+            Statement::JsxElement { tag, attributes, children, .. } => {
+                let tag = match tag {
+                    JsxTag::Member(member) => Some(&mut **member),
+                    JsxTag::Ident(_) => None
+                };
+                tag.into_iter()
+                    .chain(attributes.iter_mut().flat_map(|attribute| attribute.children_mut()))
+                    .chain(children.iter_mut().flat_map(|child| child.children_mut()))
+                    .collect()
+            },
+            #[cfg(feature = "jsx")]
+            Statement::JsxFragment { children } => children.iter_mut().flat_map(|child| child.children_mut()).collect(),
+            Statement::Export { declaration, .. } => vec![declaration],
+            Statement::Labeled { body, .. } => vec![body],
+            Statement::Commented { statement, .. } | Statement::Mapped { statement, .. } => vec![statement],
+            Statement::ClassDecl { superclass, body, .. } => {
+                superclass.iter_mut().map(|child| &mut **child)
+                    .chain(body.iter_mut().flat_map(|member| member.children_mut()))
+                    .collect()
+            },
+            Statement::Block(block) => block.statements.iter_mut().collect(),
+            _ => Vec::new()
+        }
+    }
+
+    /// Check if the statement is an expression (can be used as a value).
+    /// Raw code is neither an expression nor a declaration, as its contents are unknown.
+    pub fn is_expression(&self) -> bool {
+        match self {
+            Statement::Commented { statement, .. } | Statement::Mapped { statement, .. } => statement.is_expression(),
+            #[cfg(feature = "typescript")]
+            Statement::TsAsConst(_) | Statement::TsAssertion { .. } | Statement::TsNonNull(_) => true,
+            #[cfg(feature = "jsx")]
+            Statement::JsxElement { .. } | Statement::JsxFragment { .. } => true,
+            _ => matches!(
+                self,
+                Statement::Literal { .. } | Statement::StringLiteral(_) | Statement::RegexLiteral { .. }
+                    | Statement::BigIntLiteral(_) | Statement::Null | Statement::Undefined | Statement::NaN
+                    | Statement::Infinity | Statement::Binary { .. } | Statement::Instanceof { .. } | Statement::InExpr { .. }
+                    | Statement::ConditionalChain { .. }
+                    | Statement::Assign { .. } | Statement::Identifier(_) | Statement::MemberAccess { .. }
+                    | Statement::Call { .. } | Statement::Index { .. } | Statement::TemplateLiteral { .. }
+                    | Statement::TaggedTemplate { .. } | Statement::Await(_) | Statement::TypeOf(_) | Statement::VoidExpr(_)
+                    | Statement::Yield { .. } | Statement::Sequence(_)
+                    | Statement::ImportMeta
+            )
+        }
+    }
+
+    /// Check if the statement is a literal value (eg. `42`, `'foo'` or `10n`).
+    pub fn is_literal(&self) -> bool {
+        matches!(self, Statement::Literal { .. } | Statement::StringLiteral(_) | Statement::BigIntLiteral(_))
+    }
+
+    /// Get the type a declaration file gives a variable initialized with the statement when the initializer
+    /// isn't kept (eg. `number` for `let x = 1`), values that aren't literals are `unknown`.
+    pub(crate) fn declared_type(&self) -> &'static str {
+        match self {
+            Statement::StringLiteral(_) | Statement::TemplateLiteral { .. } => "string",
+            Statement::BigIntLiteral(_) => "bigint",
+            Statement::Literal { value } if value == "true" || value == "false" => "boolean",
+            Statement::Literal { value } if value.starts_with(|c: char| c.is_ascii_digit() || c == '.' || c == '-') => "number",
+            Statement::NaN | Statement::Infinity => "number",
+            Statement::Commented { statement, .. } | Statement::Mapped { statement, .. } => statement.declared_type(),
+            _ => "unknown"
+        }
+    }
+
+    /// Check if the statement is a declaration (eg. `let x = 1` or `class Foo {}`).
+    pub fn is_declaration(&self) -> bool {
+        match self {
+            Statement::VarDecl { .. } | Statement::MultiVarDecl { .. } | Statement::DestructureDecl { .. }
+                | Statement::ClassDecl { .. } => true,
+            #[cfg(feature = "typescript")]
+            Statement::TsInterface { .. } | Statement::TsTypeAlias { .. }
+                | Statement::TsDeclare(_) | Statement::TsDeclareModule { .. } => true,
+            Statement::Commented { statement, .. } | Statement::Mapped { statement, .. } => statement.is_declaration(),
+            _ => false
+        }
+    }
+
+    /// Fail if the statement can't be used as a value.
+    /// Raw code is trusted and classes are allowed as class expressions.
+    pub(crate) fn check_expression(&self) -> Result<(), CodegenError> {
+        if self.is_expression() || matches!(self, Statement::Raw(_) | Statement::ClassDecl { .. }) {
+            return Ok(());
+        }
+        Err(CodegenError::InvalidStatement { context: format!("Expected expression, got {:?}", self) })
+    }
+
+    /// Get the JavaScript operator precedence of the expression (higher binds tighter).
+    /// Statements that are not expressions and raw code that is not a simple name, member access or call
+    /// have precedence 0, so they are always parenthesized when used as operands.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            Statement::Raw(code) if is_simple_expression(code) => PRIMARY_PRECEDENCE,
+            Statement::Literal { value } | Statement::BigIntLiteral(value) => {
+                if value.starts_with('-') { PREFIX_PRECEDENCE } else { PRIMARY_PRECEDENCE }
+            },
+            Statement::StringLiteral(_) | Statement::RegexLiteral { .. } | Statement::Identifier(_) | Statement::TemplateLiteral { .. }
+                | Statement::Null | Statement::Undefined | Statement::NaN | Statement::Infinity => PRIMARY_PRECEDENCE,
+            #[cfg(feature = "typescript")]
+            Statement::TsAsConst(_) => PRIMARY_PRECEDENCE,
+            #[cfg(feature = "typescript")]
+            Statement::TsAssertion { .. } => binary_precedence("as"),
+            #[cfg(feature = "typescript")]
+            Statement::TsNonNull(_) => MEMBER_PRECEDENCE,
+            #[cfg(feature = "jsx")]
+            Statement::JsxElement { .. } | Statement::JsxFragment { .. } => PRIMARY_PRECEDENCE,
+            Statement::ImportMeta | Statement::MemberAccess { .. } | Statement::Call { .. } | Statement::Index { .. }
+                | Statement::TaggedTemplate { .. } => {
+                MEMBER_PRECEDENCE
+            },
+            Statement::Await(_) | Statement::TypeOf(_) | Statement::VoidExpr(_) => PREFIX_PRECEDENCE,
+            Statement::Binary { operator, .. } => binary_precedence(operator),
+            Statement::Instanceof { .. } => binary_precedence("instanceof"),
+            Statement::InExpr { .. } => binary_precedence("in"),
+            Statement::ConditionalChain { guards, final_expr } if guards.is_empty() => final_expr.precedence(),
+            Statement::ConditionalChain { .. } => binary_precedence("&&"),
+            Statement::Assign { .. } | Statement::Yield { .. } => ASSIGNMENT_PRECEDENCE,
+            Statement::Sequence(_) => SEQUENCE_PRECEDENCE,
+            Statement::Mapped { statement, .. } => statement.precedence(),
+            _ => 0
+        }
+    }
+
+    /// Get the terminator of the statement when used at block level.
+    fn terminator(&self, options: &CodegenOptions) -> &'static str {
+        if let Statement::Mapped { statement, .. } = self {
+            return statement.terminator(options);
+        }
+        // Comments terminate the statements they are attached to, expression statements terminate themselves.
+        if matches!(
+            self,
+            Statement::LineComment(_) | Statement::BlockComment(_) | Statement::DocComment(_) | Statement::Commented { .. }
+                | Statement::ExpressionStatement(_)
+        ) {
+            return "";
+        }
+        // Minified statements are all on one line, so they must be separated.
+        let needs_semicolon = match options.semicolons {
+            _ if options.minify => !matches!(self, Statement::Block(_)),
+            SemicolonMode::Always => !matches!(self, Statement::Block(_)),
+            SemicolonMode::Never => false,
+            SemicolonMode::AsNeeded => self.needs_semicolon()
+        };
+        if needs_semicolon { ";" } else { "" }
+    }
+
+    /// Check if the statement must be terminated with a semicolon when used at block level.
+    /// Raw code is emitted as-is, blocks and classes end with `}`.
+    fn needs_semicolon(&self) -> bool {
+        match self {
+            Statement::Raw(_) | Statement::Block(_) | Statement::ClassDecl { .. } => false,
+            #[cfg(feature = "typescript")]
+            Statement::TsInterface { .. } | Statement::TsDeclareModule { .. } => false,
+            #[cfg(feature = "typescript")]
+            Statement::TsDeclare(declaration) => declaration.needs_semicolon(),
+            Statement::Labeled { body, .. } => body.needs_semicolon(),
+            Statement::Mapped { statement, .. } => statement.needs_semicolon(),
+            Statement::Export { declaration, is_default: true } => !matches!(**declaration, Statement::ClassDecl { .. }),
+            Statement::Export { declaration, .. } => declaration.needs_semicolon(),
+            _ => true
+        }
+    }
+
+    /// Create a new variable declaration without a type annotation.
+    pub fn var_decl(var_type: VarType, name: &str, initializer: Option<Statement>) -> Self {
+        Statement::VarDecl {
+            var_type,
+            name: name.to_string(),
+            initializer: initializer.map(Box::new),
+            #[cfg(feature = "typescript")]
+            type_annotation: None
+        }
+    }
+
+    /// Create a new default export (eg. `export default foo`).
+    pub fn export_default(declaration: impl Into<Statement>) -> Self {
+        Statement::ExportDefault(Box::new(declaration.into()))
+    }
+
+    /// Create a new default export (eg. `export default foo`), written like a variant: `Statement::ExportDefault(value)`.
+    /// Default exports are represented as `Statement::Export { is_default: true, .. }`, match on that instead.
+    #[allow(non_snake_case)]
+    pub fn ExportDefault(declaration: Box<Statement>) -> Self {
+        Statement::Export { declaration, is_default: true }
+    }
+
+    pub fn boxed(self) -> Box<Self> {
+        Box::new(self)
+    }
+
+    /// Attach a line comment to the statement (eg. `let x = 1; // foo`).
+    pub fn with_comment(self, comment: &str) -> Self {
+        Statement::Commented {
+            statement: Box::new(self),
+            comment: comment.to_string()
+        }
+    }
+
+    /// Access a property of the expression (eg. `foo.bar`).
+    pub fn member(self, property: &str) -> Self {
+        Statement::MemberAccess {
+            object: Box::new(self),
+            property: property.to_string()
+        }
+    }
+
+    /// Call the expression with the arguments (eg. `foo(a, b)`).
+    pub fn call(self, args: Vec<Statement>) -> Self {
+        Statement::Call {
+            callee: Box::new(self),
+            args,
+            #[cfg(feature = "typescript")]
+            type_args: Vec::new()
+        }
+    }
+
+    /// Call the expression with type arguments and arguments (eg. `identity<string>(x)`).
+    #[cfg(feature = "typescript")]
+    pub fn call_with_type_args(self, type_args: Vec<TsType>, args: Vec<Statement>) -> Self {
+        Statement::Call {
+            callee: Box::new(self),
+            args,
+            type_args
+        }
+    }
+
+    /// Set the type arguments of a call (eg. `identity<string>(x)`), fails if the statement is not a call.
+    #[cfg(feature = "typescript")]
+    pub fn with_type_args(mut self, new_type_args: Vec<TsType>) -> Result<Self, CodegenError> {
+        match &mut self {
+            Statement::Call { type_args, .. } => *type_args = new_type_args,
+            statement => return Err(CodegenError::InvalidStatement { context: format!("Expected call, got {:?}", statement) })
+        }
+        Ok(self)
+    }
+
+    /// Call a method of the expression with the arguments (eg. `foo.bar(a, b)`), calls can be chained.
+    pub fn method_call(self, name: &str, args: Vec<Statement>) -> Self {
+        self.member(name).call(args)
+    }
+
+    /// Create an `html` tagged template (eg. ``html`<div>${content}</div>` ``).
+    pub fn html_template(quasis: Vec<&str>, expressions: Vec<Statement>) -> Self {
+        Self::tagged_with("html", quasis, expressions)
+    }
+
+    /// Create a `css` tagged template (eg. ``css`color: ${color};` ``).
+    pub fn css_template(quasis: Vec<&str>, expressions: Vec<Statement>) -> Self {
+        Self::tagged_with("css", quasis, expressions)
+    }
+
+    /// Create a `gql` tagged template (eg. ``gql`query { user(id: ${id}) { name } }` ``).
+    pub fn gql_template(quasis: Vec<&str>, expressions: Vec<Statement>) -> Self {
+        Self::tagged_with("gql", quasis, expressions)
+    }
+
+    fn tagged_with(tag: &str, quasis: Vec<&str>, expressions: Vec<Statement>) -> Self {
+        Statement::TaggedTemplate {
+            tag: Box::new(Statement::Identifier(tag.to_string())),
+            template: Box::new(Statement::TemplateLiteral {
+                quasis: quasis.into_iter().map(str::to_string).collect(),
+                expressions
+            })
+        }
+    }
+
+    /// Attach the location in an original source the statement was created from.
+    pub fn with_source(self, location: SourceLocation) -> Self {
+        Statement::Mapped {
+            statement: Box::new(self),
+            location
+        }
+    }
+
+    /// Create a hexadecimal number literal (eg. `0xFF`).
+    pub fn numeric_hex(value: u64) -> Self {
+        Statement::Literal { value: format!("0x{:X}", value) }
+    }
+
+    /// Create an octal number literal (eg. `0o77`).
+    pub fn numeric_octal(value: u64) -> Self {
+        Statement::Literal { value: format!("0o{:o}", value) }
+    }
+
+    /// Create a binary number literal (eg. `0b1010`).
+    pub fn numeric_binary(value: u64) -> Self {
+        Statement::Literal { value: format!("0b{:b}", value) }
+    }
+
+    /// Create a decimal number literal, optionally grouping digits with `_` (eg. `1_000_000`).
+    pub fn numeric_with_separator(value: i64, separator: bool) -> Self {
+        if !separator {
+            return Statement::Literal { value: value.to_string() };
+        }
+
+        let digits = value.unsigned_abs().to_string();
+        let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                grouped.push('_');
+            }
+            grouped.push(digit);
+        }
+        let sign = if value < 0 { "-" } else { "" };
+        Statement::Literal { value: format!("{}{}", sign, grouped) }
+    }
+
+    /// Create a string literal always wrapped in double quotes, regardless of `CodegenOptions`.
+    pub fn from_str_double_quoted(value: &str) -> Self {
+        Statement::Literal { value: QuoteStyle::Double.quote(value) }
+    }
+
+    /// Create a string literal always wrapped in single quotes, regardless of `CodegenOptions`.
+    pub fn from_str_single_quoted(value: &str) -> Self {
+        Statement::Literal { value: QuoteStyle::Single.quote(value) }
+    }
+}
+
+/// Characters allowed as regular expression flags.
+const REGEX_FLAGS: &str = "dgimsuvy";
+
+const PRIMARY_PRECEDENCE: u8 = 18;
+const MEMBER_PRECEDENCE: u8 = 17;
+const PREFIX_PRECEDENCE: u8 = 14;
+pub(crate) const ASSIGNMENT_PRECEDENCE: u8 = 2;
+const SEQUENCE_PRECEDENCE: u8 = 1;
+
+const BINARY_OPERATORS: &[&str] = &[
+    "**", "*", "/", "%", "+", "-", "<<", ">>", ">>>", "<", "<=", ">", ">=", "in", "instanceof",
+    "==", "!=", "===", "!==", "&", "^", "|", "&&", "||", "??"
+];
+const ASSIGNMENT_OPERATORS: &[&str] = &[
+    "=", "+=", "-=", "*=", "/=", "%=", "**=", "<<=", ">>=", ">>>=", "&=", "^=", "|=", "&&=", "||=", "??="
+];
+
+/// Get the precedence of a binary operator, unknown operators get the lowest precedence.
+fn binary_precedence(operator: &str) -> u8 {
+    match operator {
+        "**" => 13,
+        "*" | "/" | "%" => 12,
+        "+" | "-" => 11,
+        "<<" | ">>" | ">>>" => 10,
+        "<" | "<=" | ">" | ">=" | "in" | "instanceof" | "as" => 9,
+        "==" | "!=" | "===" | "!==" => 8,
+        "&" => 7,
+        "^" => 6,
+        "|" => 5,
+        "&&" => 4,
+        "||" | "??" => 3,
+        _ => 1
+    }
+}
+
+/// Check if raw code is a name, member access or call (eg. `foo.bar(1 + 2)[0]`),
+/// anything outside of brackets must be a part of a name.
+fn is_simple_expression(code: &str) -> bool {
+    let mut depth = 0usize;
+    for c in code.chars() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => match depth.checked_sub(1) {
+                Some(new_depth) => depth = new_depth,
+                None => return false
+            },
+            _ if depth > 0 => {},
+            c if c.is_alphanumeric() || matches!(c, '_' | '$' | '.') => {},
+            _ => return false
+        }
+    }
+    !code.is_empty() && depth == 0
+}
+
+/// Generate a binary expression, parenthesizing the operands where precedence requires it.
+fn generate_binary(
+    left: &Statement,
+    operator: &str,
+    right: &Statement,
+    indent: usize,
+    options: &CodegenOptions
+) -> Result<String, CodegenError> {
+    let precedence = binary_precedence(operator);
+    let right_associative = operator == "**";
+    // Unary expressions can't be the base of `**` without parentheses (eg. `(-2) ** 2`).
+    let left_parens = left.precedence() < precedence
+        || (right_associative && left.precedence() <= PREFIX_PRECEDENCE)
+        || mixes_nullish(operator, left);
+    let right_parens = right.precedence() < precedence
+        || (!right_associative && right.precedence() == precedence)
+        || mixes_nullish(operator, right);
+    let left = left.generate_parenthesized(left_parens, indent, options)?;
+    let right = right.generate_parenthesized(right_parens, indent, options)?;
+    Ok(options.join_tokens(&[&left, operator, &right]))
+}
+
+/// Check if the operand must be parenthesized because `??` can't be mixed with `&&` or `||`.
+fn mixes_nullish(operator: &str, operand: &Statement) -> bool {
+    let operand_operator = match operand {
+        Statement::Binary { operator, .. } => operator.as_str(),
+        Statement::ConditionalChain { .. } => "&&",
+        _ => return false
+    };
+    match operator {
+        "??" => matches!(operand_operator, "&&" | "||"),
+        "&&" | "||" => operand_operator == "??",
+        _ => false
+    }
+}
+
+/// Get the variables declared by a statement directly in the current scope.
+fn scope_var_decls(statement: &Statement) -> Vec<(&VarType, &str)> {
+    match statement {
+        Statement::VarDecl { var_type, name, .. } => vec![(var_type, name)],
+        Statement::MultiVarDecl { var_type, declarators } => {
+            declarators.iter().map(|(name, _)| (var_type, name.as_str())).collect()
+        },
+        Statement::DestructureDecl { var_type, pattern, .. } => {
+            pattern.bindings().into_iter().map(|name| (var_type, name)).collect()
+        },
+        Statement::Export { declaration, .. } => scope_var_decls(declaration),
+        Statement::Commented { statement, .. } | Statement::Mapped { statement, .. } => scope_var_decls(statement),
+        _ => Vec::new()
+    }
+}
+
+/// Collect variable declarations of a statement and the statements nested in it.
+fn collect_var_decls<'a>(statement: &'a Statement, declarations: &mut Vec<(&'a VarType, &'a str)>) {
+    match statement {
+        Statement::VarDecl { .. } | Statement::MultiVarDecl { .. } | Statement::DestructureDecl { .. } => {
+            declarations.extend(scope_var_decls(statement))
+        },
+        Statement::Export { declaration, .. } => collect_var_decls(declaration, declarations),
+        Statement::Labeled { body, .. } => collect_var_decls(body, declarations),
+        Statement::Commented { statement, .. } | Statement::Mapped { statement, .. } => collect_var_decls(statement, declarations),
+        Statement::Block(block) => declarations.extend(block.find_var_decls()),
+        _ => {}
+    }
+}
+
+/// Fail if a rest element of a pattern is not the last one or is combined with other parts
+/// (a default value, alias or hole), elements are given as `(is_rest, has_other_parts)`.
+fn check_rest(elements: impl ExactSizeIterator<Item = (bool, bool)>) -> Result<(), CodegenError> {
+    let len = elements.len();
+    for (i, (is_rest, has_other_parts)) in elements.enumerate() {
+        if is_rest && (i + 1 != len || has_other_parts) {
+            return Err(CodegenError::InvalidStatement {
+                context: "Rest element must be the last one and can't have a default value".to_string()
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Generate the default value of a pattern element including the `=` (eg. ` = 1`).
+fn generate_default(default: &Option<Box<Statement>>, indent: usize, options: &CodegenOptions) -> Result<String, CodegenError> {
+    match default {
+        Some(default) => {
+            let value = default.generate_parenthesized(default.precedence() < ASSIGNMENT_PRECEDENCE, indent, options)?;
+            Ok(format!("{}={}{}", options.space(), options.space(), value))
+        },
+        None => Ok(String::new())
+    }
+}
+
+/// Prefix each line of a multi-line comment with ` * `.
+pub(crate) fn comment_lines(text: &str, prefix: &str) -> String {
+    text.lines()
+        .map(|line| match line {
+            "" => format!("{} *\n", prefix),
+            line => format!("{} * {}\n", prefix, line)
+        })
+        .collect()
+}
+
+/// Escape the text part of a template literal, only backslashes, backticks and `${` have to be escaped.
+fn escape_template(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('`', "\\`").replace("${", "\\${")
+}
+
+/// Escape all `/` characters in a regex pattern that are not already escaped.
+fn escape_regex_pattern(pattern: &str) -> String {
+    let mut escaped = String::with_capacity(pattern.len());
+    let mut is_escaping = false;
+    for c in pattern.chars() {
+        if c == '/' && !is_escaping {
+            escaped.push('\\');
+        }
+        is_escaping = c == '\\' && !is_escaping;
+        escaped.push(c);
+    }
+    escaped
+}
+
+impl Block {
+    /// Create a new block.
+    pub fn new(indent: usize) -> Self {
+        Self {
+            indent,
+            statements: Vec::new(),
+            context: BlockContext::Normal,
+        }
+    }
+
+    /// Set the kind of function the block belongs to.
+    pub fn with_context(mut self, context: BlockContext) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Create a new builder for a block at the given indentation level.
+    pub fn builder(indent: usize) -> BlockBuilder {
+        BlockBuilder {
+            block: Self::new(indent)
+        }
+    }
+
+    /// Create a new empty block nested one level deeper than this block, in the same context.
+    pub fn sub_block(&self) -> Block {
+        Block::new(self.indent + 1).with_context(self.context)
+    }
+
+    /// Add a nested block to the block (see `sub_block`).
+    pub fn push_block(&mut self, block: Block) -> &mut Self {
+        self.push(Statement::Block(Box::new(block)))
+    }
+
+    /// Add a statement to the block, fails if it uses `await` or `yield` where the context of the block doesn't allow it.
+    pub fn stmt(&mut self, statement: Statement) -> Result<&mut Self, CodegenError> {
+        self.context.check(&statement)?;
+        Ok(self.push(statement))
+    }
+
+    /// Add a statement to the block, it is checked against the context when the block is generated.
+    fn push(&mut self, statement: Statement) -> &mut Self {
+        self.statements.push(statement);
+        self
+    }
+
+    /// Move all statements of another block to the end of this block.
+    /// The statements are indented according to this block, the other block's indent is ignored.
+    pub fn extend(&mut self, other: Block) -> &mut Self {
+        self.extend_stmts(other.statements)
+    }
+
+    /// Add multiple statements to the end of the block.
+    /// They are checked against the context of the block when it is generated.
+    pub fn extend_stmts(&mut self, statements: Vec<Statement>) -> &mut Self {
+        self.statements.extend(statements);
+        self
+    }
+
+    /// Walk all statements of the block (see `Statement::walk`).
+    pub fn walk<'a, F: FnMut(&'a Statement)>(&'a self, mut visitor: F) {
+        for statement in &self.statements {
+            statement.walk(&mut visitor);
+        }
+    }
+
+    /// Walk all statements of the block for modification (see `Statement::walk_mut`).
+    pub fn walk_mut<F: FnMut(&mut Statement)>(&mut self, mut visitor: F) {
+        for statement in &mut self.statements {
+            statement.walk_mut(&mut visitor);
+        }
+    }
+
+    /// Find all variables declared in the block, including the ones in nested blocks.
+    pub fn find_var_decls(&self) -> Vec<(&VarType, &str)> {
+        let mut declarations = Vec::new();
+        for statement in &self.statements {
+            collect_var_decls(statement, &mut declarations);
+        }
+        declarations
+    }
+
+    /// Check if the block (or any of its nested blocks) declares a variable with the given name.
+    pub fn declares(&self, name: &str) -> bool {
+        self.find_var_decls().iter().any(|(_, declared)| *declared == name)
+    }
+
+    /// Rename the variables declared in the block (including nested blocks) to fresh names made of the prefix and an index
+    /// (eg. `_a0`, `_a1`), updating all references to them. Returns the mapping from the old names to the new ones.
+    /// Exported declarations keep their names and export lists keep exporting under the old names. Names that class
+    /// methods declare or take as parameters are not renamed, as they shadow the block's variables there.
+    /// References inside raw code are not updated.
+    pub fn alpha_rename(&mut self, prefix: &str) -> HashMap<String, String> {
+        let mut renames = HashMap::new();
+        {
+            let mut excluded = Vec::new();
+            let mut used: Vec<&str> = self.find_var_decls().into_iter().map(|(_, name)| name).collect();
+            self.walk(|statement| match statement {
+                Statement::Export { declaration, .. } => excluded.extend(declaration.collect_declarations()),
+                Statement::ClassDecl { body, .. } => {
+                    for member in body {
+                        if let ClassMember::Method { params, body, .. } = member {
+                            excluded.extend(params.iter().map(|param| param.name.as_str()));
+                            excluded.extend(body.find_var_decls().into_iter().map(|(_, name)| name));
+                        }
+                    }
+                },
+                _ => used.extend(statement.collect_identifiers())
+            });
+
+            let mut index = 0;
+            for (_, name) in self.find_var_decls() {
+                if excluded.contains(&name) || renames.contains_key(name) {
+                    continue;
+                }
+                let fresh = loop {
+                    let fresh = format!("{}{}", prefix, index);
+                    index += 1;
+                    if !used.contains(&fresh.as_str()) {
+                        break fresh;
+                    }
+                };
+                renames.insert(name.to_string(), fresh);
+            }
+        }
+
+        let rename = |name: &mut String| {
+            if let Some(fresh) = renames.get(name) {
+                *name = fresh.clone();
+            }
+        };
+        self.walk_mut(|statement| match statement {
+            Statement::Identifier(name) | Statement::Assign { name, .. } | Statement::VarDecl { name, .. } => rename(name),
+            Statement::MultiVarDecl { declarators, .. } => declarators.iter_mut().for_each(|(name, _)| rename(name)),
+            Statement::DestructureDecl { pattern: DestructurePattern::Object(properties), .. } => {
+                for property in properties {
+                    match &mut property.alias {
+                        Some(alias) => rename(alias),
+                        // Rest properties can't be aliased, their key is the binding.
+                        None if property.rest => rename(&mut property.key),
+                        None if renames.contains_key(&property.key) => property.alias = renames.get(&property.key).cloned(),
+                        None => {}
+                    }
+                }
+            },
+            Statement::DestructureDecl { pattern: DestructurePattern::Array(elements), .. } => {
+                elements.iter_mut().filter_map(|element| element.binding.as_mut()).for_each(rename)
+            },
+            Statement::ExportList { names, from_path: None } | Statement::ModuleExports(names) => {
+                for specifier in names.iter_mut().filter(|specifier| renames.contains_key(&specifier.local)) {
+                    specifier.exported.get_or_insert_with(|| specifier.local.clone());
+                    rename(&mut specifier.local);
+                }
+            },
+            #[cfg(feature = "jsx")]
+            Statement::JsxElement { tag: JsxTag::Ident(name), .. } => rename(name),
+            _ => {}
+        });
+        renames
+    }
+
+    /// Create a copy of the block at another indentation level.
+    /// All nested blocks (including the ones inside other statements and class method bodies)
+    /// are shifted by the same amount as this block.
+    pub fn with_indent(&self, new_indent: usize) -> Block {
+        let shift = |indent: &mut usize| *indent = (*indent + new_indent).saturating_sub(self.indent);
+        let mut block = self.clone();
+        block.walk_mut(|statement| match statement {
+            Statement::Block(nested) => shift(&mut nested.indent),
+            Statement::ClassDecl { body, .. } => {
+                for member in body {
+                    if let ClassMember::Method { body, .. } = member {
+                        shift(&mut body.indent);
+                    }
+                }
+            },
+            #[cfg(feature = "typescript")]
+            Statement::TsDeclareModule { body, .. } => shift(&mut body.indent),
+            _ => {}
+        });
+        block.indent = new_indent;
+        block
+    }
+
+    /// Move the `var` declarations of the block to its start, like JavaScript engines hoist them.
+    /// The declarations are moved without their initializers, which are replaced by assignments where
+    /// the declarations were (eg. `foo(); var x = 1;` becomes `var x; foo(); x = 1;`).
+    /// Only the block's own statements are hoisted, a name declared more than once is hoisted once.
+    pub fn hoist_var_decls(&mut self) -> &mut Self {
+        let mut hoisted = Vec::new();
+        let mut names = Vec::new();
+        let mut statements = Vec::with_capacity(self.statements.len());
+        for mut statement in std::mem::take(&mut self.statements) {
+            let Statement::VarDecl { var_type: VarType::Var, name, initializer, .. } = &mut statement else {
+                statements.push(statement);
+                continue;
+            };
+            if let Some(value) = initializer.take() {
+                statements.push(Statement::Assign { name: name.clone(), operator: "=".to_string(), value });
+            }
+            if !names.contains(name) {
+                names.push(name.clone());
+                hoisted.push(statement);
+            }
+        }
+        hoisted.extend(statements);
+        self.statements = hoisted;
+        self
+    }
+
+    /// Find names declared more than once in this block's own scope (nested blocks are not checked).
+    /// Redeclaring with `var` is allowed, any redeclaration involving `let` or `const` is reported.
+    pub fn check_duplicate_decls(&self) -> Vec<String> {
+        let mut declared: Vec<(&VarType, &str)> = Vec::new();
+        let mut duplicates = Vec::new();
+        for (var_type, name) in self.statements.iter().flat_map(scope_var_decls) {
+            let conflicts = declared.iter()
+                .any(|(other_type, other)| *other == name && (*var_type != VarType::Var || **other_type != VarType::Var));
+            if conflicts && !duplicates.iter().any(|duplicate| duplicate == name) {
+                duplicates.push(name.to_string());
+            }
+            declared.push((var_type, name));
+        }
+        duplicates
+    }
+
+    /// Add the `"use client"` directive at the start of the block.
+    pub fn add_use_client(&mut self) -> &mut Self {
+        self.statements.insert(0, Statement::from_str_double_quoted("use client"));
+        self
+    }
+
+    /// Add the `"use server"` directive at the start of the block.
+    pub fn add_use_server(&mut self) -> &mut Self {
+        self.statements.insert(0, Statement::from_str_double_quoted("use server"));
+        self
+    }
+
+    /// Add raw code to the block.
+    pub fn raw(&mut self, code: &str) -> &mut Self {
+        self.push(Statement::Raw(code.to_string()))
+    }
+
+    /// Add a variable declaration to the block, fails if the name is not a valid identifier.
+    pub fn var_decl(&mut self, var_type: VarType, name: &str, initializer: Option<Statement>) -> Result<&mut Self, CodegenError> {
+        validate_identifier(name)?;
+        self.stmt(Statement::var_decl(var_type, name, initializer))
+    }
+
+    /// Add a variable declaration with multiple declarators to the block (eg. `let a = 1, b = 2, c`),
+    /// fails if one of the names is not a valid identifier.
+    pub fn multi_var_decl(&mut self, var_type: VarType, declarators: Vec<(&str, Option<Statement>)>) -> Result<&mut Self, CodegenError> {
+        for (name, _) in &declarators {
+            validate_identifier(name)?;
+        }
+        self.stmt(Statement::MultiVarDecl {
+            var_type,
+            declarators: declarators.into_iter()
+                .map(|(name, initializer)| (name.to_string(), initializer.map(Box::new)))
+                .collect()
+        })
+    }
+
+    /// Add a destructuring variable declaration to the block (eg. `const { a, b } = obj`).
+    pub fn destructure(&mut self, var_type: VarType, pattern: DestructurePattern, initializer: impl Into<Statement>) -> &mut Self {
+        self.push(Statement::DestructureDecl {
+            var_type,
+            pattern,
+            initializer: Box::new(initializer.into())
+        })
+    }
+
+    /// Add a variable declaration with a type annotation to the block (eg. `let x: number = 5`),
+    /// fails if the name is not a valid identifier.
+    #[cfg(feature = "typescript")]
+    pub fn typed_var_decl(&mut self, var_type: VarType, name: &str, type_annotation: TsType, initializer: Option<Statement>) -> Result<&mut Self, CodegenError> {
+        validate_identifier(name)?;
+        self.stmt(Statement::VarDecl {
+            var_type,
+            name: name.to_string(),
+            initializer: initializer.map(Box::new),
+            type_annotation: Some(type_annotation)
+        })
+    }
+
+    /// Add a literal to the block, fails if the value is not a literal.
+    pub fn literal(&mut self, value: impl Into<Statement>) -> Result<&mut Self, CodegenError> {
+        match value.into() {
+            value @ (Statement::Literal { .. } | Statement::StringLiteral(_)) => Ok(self.push(value)),
+            value => Err(CodegenError::InvalidStatement { context: format!("Expected literal, got {:?}", value) })
+        }
+    }
+
+    /// Add a regular expression literal to the block.
+    pub fn regex(&mut self, pattern: &str, flags: &str) -> &mut Self {
+        self.push(Statement::RegexLiteral {
+            pattern: pattern.to_string(),
+            flags: flags.to_string()
+        })
+    }
+
+    /// Add `null` to the block.
+    pub fn null(&mut self) -> &mut Self {
+        self.push(Statement::Null)
+    }
+
+    /// Add `undefined` to the block.
+    pub fn undefined(&mut self) -> &mut Self {
+        self.push(Statement::Undefined)
+    }
+
+    /// Add `NaN` to the block.
+    pub fn nan(&mut self) -> &mut Self {
+        self.push(Statement::NaN)
+    }
+
+    /// Add `Infinity` to the block.
+    pub fn infinity(&mut self) -> &mut Self {
+        self.push(Statement::Infinity)
+    }
+
+    /// Add a binary expression to the block.
+    pub fn binary(&mut self, left: impl Into<Statement>, operator: &str, right: impl Into<Statement>) -> &mut Self {
+        self.push(Statement::Binary {
+            left: Box::new(left.into()),
+            operator: operator.to_string(),
+            right: Box::new(right.into())
+        })
+    }
+
+    /// Add a guarded expression chain to the block.
+    pub fn conditional_chain(&mut self, guards: Vec<Statement>, final_expr: impl Into<Statement>) -> &mut Self {
+        self.push(Statement::ConditionalChain {
+            guards,
+            final_expr: Box::new(final_expr.into())
+        })
+    }
+
+    /// Add an assignment expression to the block.
+    pub fn assign(&mut self, name: &str, operator: &str, value: impl Into<Statement>) -> &mut Self {
+        self.push(Statement::Assign {
+            name: name.to_string(),
+            operator: operator.to_string(),
+            value: Box::new(value.into())
+        })
+    }
+
+    /// Add an await expression to the block, fails if the block is not in an async function.
+    pub fn await_expr(&mut self, value: impl Into<Statement>) -> Result<&mut Self, CodegenError> {
+        self.stmt(Statement::Await(Box::new(value.into())))
+    }
+
+    /// Add a sequence of expressions to the block.
+    pub fn sequence(&mut self, exprs: Vec<impl Into<Statement>>) -> &mut Self {
+        self.push(Statement::Sequence(exprs.into_iter().map(Into::into).collect()))
+    }
+
+    /// Add a tagged template to the block (eg. ``html`<div>${content}</div>` ``).
+    pub fn tagged_template(&mut self, tag: impl Into<Statement>, template: Statement) -> &mut Self {
+        self.push(Statement::TaggedTemplate {
+            tag: Box::new(tag.into()),
+            template: Box::new(template)
+        })
+    }
+
+    /// Add an expression statement to the block.
+    pub fn expr_stmt(&mut self, expr: impl Into<Statement>) -> &mut Self {
+        self.push(Statement::ExpressionStatement(Box::new(expr.into())))
+    }
+
+    /// Add a yield expression to the block, fails if the block is not in a generator function.
+    pub fn yield_stmt(&mut self, value: Option<Statement>, delegate: bool) -> Result<&mut Self, CodegenError> {
+        self.stmt(Statement::Yield {
+            value: value.map(|value| value.into()),
+            delegate
+        })
+    }
+
+    /// Add a TypeScript `as const` assertion to the block.
+    #[cfg(feature = "typescript")]
+    pub fn as_const(&mut self, value: impl Into<Statement>) -> &mut Self {
+        self.push(Statement::TsAsConst(Box::new(value.into())))
+    }
+
+    /// Add a TypeScript type assertion to the block.
+    #[cfg(feature = "typescript")]
+    pub fn ts_assert(&mut self, expr: impl Into<Statement>, type_ann: TsType) -> &mut Self {
+        self.push(Statement::TsAssertion {
+            expr: Box::new(expr.into()),
+            type_ann
+        })
+    }
+
+    /// Add a TypeScript non-null assertion to the block.
+    #[cfg(feature = "typescript")]
+    pub fn ts_non_null(&mut self, expr: impl Into<Statement>) -> &mut Self {
+        self.push(Statement::TsNonNull(Box::new(expr.into())))
+    }
+
+    /// Add a TypeScript ambient declaration of the given declaration to the block.
+    #[cfg(feature = "typescript")]
+    pub fn ts_declare(&mut self, inner: Statement) -> &mut Self {
+        self.push(Statement::TsDeclare(Box::new(inner)))
+    }
+
+    /// Add a TypeScript ambient module declaration to the block.
+    #[cfg(feature = "typescript")]
+    pub fn ts_declare_module(&mut self, name: &str, body: Block) -> &mut Self {
+        self.push(Statement::TsDeclareModule {
+            name: name.to_string(),
+            body: Box::new(body)
+        })
+    }
+
+    /// Add a TypeScript interface declaration to the block, fails if the name is not a valid identifier.
+    #[cfg(feature = "typescript")]
+    pub fn ts_interface(&mut self, name: &str, extends: Vec<String>, members: Vec<TsInterfaceMember>) -> Result<&mut Self, CodegenError> {
+        validate_identifier(name)?;
+        Ok(self.push(Statement::TsInterface {
+            name: name.to_string(),
+            extends,
+            members
+        }))
+    }
+
+    /// Add a TypeScript type alias to the block, fails if the name is not a valid identifier.
+    #[cfg(feature = "typescript")]
+    pub fn ts_type_alias(&mut self, name: &str, type_params: Vec<String>, definition: TsType) -> Result<&mut Self, CodegenError> {
+        validate_identifier(name)?;
+        Ok(self.push(Statement::TsTypeAlias {
+            name: name.to_string(),
+            type_params,
+            definition
+        }))
+    }
+
+    /// Add a TypeScript type-only export list to the block (eg. `export type { Foo }`),
+    /// the types are re-exported from another module if a path is given (eg. `export type { Foo } from './foo'`).
+    #[cfg(feature = "typescript")]
+    pub fn ts_export_type(&mut self, specifiers: Vec<ExportSpecifier>, from_path: Option<&str>) -> &mut Self {
+        self.push(Statement::TsExportType {
+            specifiers,
+            from_path: from_path.map(str::to_string)
+        })
+    }
+
+    /// Add an export of the given declaration to the block.
+    pub fn export(&mut self, declaration: impl Into<Statement>) -> &mut Self {
+        self.push(Statement::Export {
+            declaration: Box::new(declaration.into()),
+            is_default: false
+        })
+    }
+
+    /// Add a CommonJS import of the given names to the block (eg. `const { foo } = require('bar')`).
+    pub fn require(&mut self, imports: Vec<&str>, path: &str) -> &mut Self {
+        self.push(Statement::Require {
+            binding: None,
+            imports: imports.into_iter().map(|import| import.to_string()).collect(),
+            path: path.to_string()
+        })
+    }
+
+    /// Add CommonJS exports of the given names to the block (eg. `module.exports = { foo, bar }`).
+    pub fn exports(&mut self, names: Vec<&str>) -> &mut Self {
+        self.push(Statement::ModuleExports(names.into_iter().map(ExportSpecifier::new).collect()))
+    }
+
+    /// Add a default export of the given declaration to the block.
+    pub fn export_default(&mut self, declaration: impl Into<Statement>) -> &mut Self {
+        self.push(Statement::export_default(declaration))
+    }
+
+    /// Add an export list to the block (eg. `export { foo, bar as baz }`),
+    /// the names are re-exported from another module if a path is given (eg. `export { foo } from './foo.js'`).
+    pub fn export_list(&mut self, names: Vec<ExportSpecifier>, from_path: Option<&str>) -> &mut Self {
+        self.push(Statement::ExportList {
+            names,
+            from_path: from_path.map(str::to_string)
+        })
+    }
+
+    /// Add a `debugger` statement to the block.
+    pub fn debugger(&mut self) -> &mut Self {
+        self.push(Statement::Debugger)
+    }
+
+    /// Add a line comment to the block.
+    pub fn line_comment(&mut self, text: &str) -> &mut Self {
+        self.push(Statement::LineComment(text.to_string()))
+    }
+
+    /// Add a block comment to the block.
+    pub fn block_comment(&mut self, text: &str) -> &mut Self {
+        self.push(Statement::BlockComment(text.to_string()))
+    }
+
+    /// Add a labeled statement to the block.
+    pub fn labeled_stmt(&mut self, label: &str, body: Statement) -> &mut Self {
+        self.push(Statement::Labeled {
+            label: label.to_string(),
+            body: Box::new(body)
+        })
+    }
+
+    /// Add a class declaration to the block, fails if the name is not a valid identifier.
+    pub fn class_decl(&mut self, name: &str, superclass: Option<Statement>, body: Vec<ClassMember>) -> Result<&mut Self, CodegenError> {
+        validate_identifier(name)?;
+        self.stmt(Statement::ClassDecl {
+            name: name.to_string(),
+            superclass: superclass.map(|superclass| superclass.into()),
+            body
+        })
+    }
+
+    /// Generate the block's code.
+    pub fn generate(&self) -> Result<String, CodegenError> {
+        self.generate_with_options(&CodegenOptions::default())
+    }
+
+    /// Generate the block's code using the given options.
+    pub fn generate_with_options(&self, options: &CodegenOptions) -> Result<String, CodegenError> {
+        self.generate_indented(self.indent, options)
+    }
+
+    /// Generate the block's code, alias of `generate`.
+    pub fn to_js_string(&self) -> Result<String, CodegenError> {
+        self.generate()
+    }
+
+    /// Generate the block's code wrapped in an async `main` function that is called right away.
+    /// Useful when targeting environments without top-level `await`.
+    pub fn generate_as_async_iife(&self) -> Result<String, CodegenError> {
+        self.generate_as_async_iife_with_options(&CodegenOptions::default())
+    }
+
+    /// Generate the block's code wrapped in an async `main` function using the given options (see `generate_as_async_iife`).
+    /// The statements are checked as the body of an async function regardless of the block's context.
+    pub fn generate_as_async_iife_with_options(&self, options: &CodegenOptions) -> Result<String, CodegenError> {
+        let prefix = options.indent(self.indent);
+        let call = Statement::Identifier("main".to_string())
+            .call(Vec::new())
+            .method_call("catch", vec![Statement::Identifier("console".to_string()).member("error")]);
+        Ok(format!(
+            "{}async function main(){}{{{}{}{}}}{}{}{}{}{}",
+            prefix,
+            options.space(),
+            options.newline(),
+            self.generate_in_context(BlockContext::AsyncFunction, self.indent + 1, options)?,
+            prefix,
+            options.newline(),
+            prefix,
+            call.generate_indented(self.indent, options)?,
+            call.terminator(options),
+            options.newline()
+        ))
+    }
+
+    /// Generate the block's code and measure how long it took.
+    #[cfg(feature = "benchmarking")]
+    pub fn generate_benchmark(&self) -> Result<(String, std::time::Duration), CodegenError> {
+        let start = std::time::Instant::now();
+        let code = self.generate()?;
+        Ok((code, start.elapsed()))
+    }
+
+    /// Generate the block's code at the given indentation level instead of the block's own one.
+    /// Fails if a statement uses `await` or `yield` where the context of the block doesn't allow it.
+    pub fn generate_indented(&self, indent: usize, options: &CodegenOptions) -> Result<String, CodegenError> {
+        self.generate_in_context(self.context, indent, options)
+    }
+
+    /// Generate the block's code as the body of a function with the given context instead of the block's own one.
+    /// Nested blocks are a part of the same function, so they are generated in the same context.
+    pub(crate) fn generate_in_context(&self, context: BlockContext, indent: usize, options: &CodegenOptions) -> Result<String, CodegenError> {
+        let mut code = String::new();
+
+        for statement in &self.statements {
+            code.push_str(&self.generate_line(statement, context, indent, options)?);
+        }
+
+        Ok(code)
+    }
+
+    /// Generate a statement of the block as a terminated line at the given indentation level.
+    /// Nested blocks are generated as their own lines, keeping their indentation relative to this block.
+    fn generate_line(&self, statement: &Statement, context: BlockContext, indent: usize, options: &CodegenOptions) -> Result<String, CodegenError> {
+        match statement {
+            Statement::Block(block) => block.generate_in_context(context, self.nested_indent(block, indent), options),
+            _ => {
+                context.check(statement)?;
+                Ok(format!(
+                    "{}{}{}{}",
+                    options.indent(indent),
+                    statement.generate_indented(indent, options)?,
+                    statement.terminator(options),
+                    options.newline()
+                ))
+            }
+        }
+    }
+
+    /// Get the indentation level of a nested block when this block is generated at the given level.
+    fn nested_indent(&self, block: &Block, indent: usize) -> usize {
+        indent + block.indent.saturating_sub(self.indent)
+    }
+
+    /// Generate the block's code and write it to any output, one statement at a time.
+    /// Unlike `generate_with_options`, the code of the whole block is never held in memory.
+    pub fn generate_to(&self, output: &mut impl std::io::Write, options: &CodegenOptions) -> Result<(), CodegenError> {
+        for statement in &self.statements {
+            output.write_all(self.generate_line(statement, self.context, self.indent, options)?.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Generate the block's code, recording the positions of mapped statements in the sink.
+    pub fn generate_with_source_map(&self, options: &CodegenOptions, sink: &mut dyn SourceMapSink) -> Result<String, CodegenError> {
+        self.generate_mapped(self.indent, options, (0, 0), sink)
+    }
+
+    /// Generate the block's code starting at the given generated position (line and column).
+    pub(crate) fn generate_mapped(
+        &self,
+        indent: usize,
+        options: &CodegenOptions,
+        start: (u32, u32),
+        sink: &mut dyn SourceMapSink
+    ) -> Result<String, CodegenError> {
+        self.generate_mapped_in_context(self.context, indent, options, start, sink)
+    }
+
+    fn generate_mapped_in_context(
+        &self,
+        context: BlockContext,
+        indent: usize,
+        options: &CodegenOptions,
+        start: (u32, u32),
+        sink: &mut dyn SourceMapSink
+    ) -> Result<String, CodegenError> {
+        let mut code = String::new();
+
+        for statement in &self.statements {
+            if let Statement::Block(block) = statement {
+                let position = end_position(start, &code);
+                code.push_str(&block.generate_mapped_in_context(context, self.nested_indent(block, indent), options, position, sink)?);
+                continue;
+            }
+            context.check(statement)?;
+            code.push_str(&options.indent(indent));
+            let position = end_position(start, &code);
+            code.push_str(&statement.generate_mapped(indent, options, position, sink)?);
+            code.push_str(statement.terminator(options));
+            code.push_str(options.newline());
+        }
+
+        Ok(code)
+    }
+}
+
+impl From<&str> for Statement {
+    fn from(code: &str) -> Self {
+        Statement::StringLiteral(code.to_string())
+    }
+}
+
+impl From<String> for Statement {
+    fn from(code: String) -> Self {
+        Statement::StringLiteral(code)
+    }
+}
+
+impl From<i32> for Statement {
+    fn from(code: i32) -> Self {
+        Statement::Literal { value: code.to_string() }
+    }
+}
+
+impl From<f32> for Statement {
+    fn from(code: f32) -> Self {
+        if code.is_finite() {
+            Statement::Literal { value: code.to_string() }
+        } else {
+            Statement::from(code as f64)
+        }
+    }
+}
+
+impl<T: Into<Statement>> From<Option<T>> for Statement {
+    fn from(value: Option<T>) -> Self {
+        match value {
+            Some(value) => value.into(),
+            None => Statement::Null
+        }
+    }
+}
+
+impl From<i128> for Statement {
+    fn from(value: i128) -> Self {
+        Statement::BigIntLiteral(value.to_string())
+    }
+}
+
+impl From<bool> for Statement {
+    fn from(code: bool) -> Self {
+        Statement::Literal { value: code.to_string() }
+    }
+}
+
+impl From<i64> for Statement {
+    fn from(code: i64) -> Self {
+        Statement::Literal { value: code.to_string() }
+    }
+}
+
+impl From<u32> for Statement {
+    fn from(code: u32) -> Self {
+        Statement::Literal { value: code.to_string() }
+    }
+}
+
+impl From<u64> for Statement {
+    fn from(code: u64) -> Self {
+        Statement::Literal { value: code.to_string() }
+    }
+}
+
+impl From<usize> for Statement {
+    fn from(code: usize) -> Self {
+        Statement::Literal { value: code.to_string() }
+    }
+}
+
+impl From<f64> for Statement {
+    fn from(code: f64) -> Self {
+        // Rust writes these as `NaN`, `inf` and `-inf`, which are not numbers in JavaScript.
+        match code {
+            code if code.is_nan() => Statement::NaN,
+            f64::INFINITY => Statement::Infinity,
+            f64::NEG_INFINITY => Statement::Literal { value: "-Infinity".to_string() },
+            code => Statement::Literal { value: code.to_string() }
+        }
+    }
+}
+
+impl std::fmt::Display for Statement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        display_generated(f, self.generate())
+    }
+}
+
+/// Builder constructing a block in a single expression, every method takes and returns the builder by value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockBuilder {
+    block: Block,
+}
+
+impl BlockBuilder {
+    /// Set the kind of function the block belongs to.
+    pub fn context(mut self, context: BlockContext) -> Self {
+        self.block.context = context;
+        self
+    }
+
+    /// Add a statement to the block, fails if it uses `await` or `yield` where the context of the block doesn't allow it.
+    pub fn stmt(mut self, statement: Statement) -> Result<Self, CodegenError> {
+        self.block.stmt(statement)?;
+        Ok(self)
+    }
+
+    /// Add multiple statements to the end of the block.
+    pub fn stmts(mut self, statements: Vec<Statement>) -> Self {
+        self.block.extend_stmts(statements);
+        self
+    }
+
+    /// Add raw code to the block.
+    pub fn raw(mut self, code: &str) -> Self {
+        self.block.raw(code);
+        self
+    }
+
+    /// Add a variable declaration to the block, fails if the name is not a valid identifier.
+    pub fn var_decl(mut self, var_type: VarType, name: &str, initializer: Option<Statement>) -> Result<Self, CodegenError> {
+        self.block.var_decl(var_type, name, initializer)?;
+        Ok(self)
+    }
+
+    /// Add a class declaration to the block, fails if the name is not a valid identifier.
+    pub fn class_decl(mut self, name: &str, superclass: Option<Statement>, body: Vec<ClassMember>) -> Result<Self, CodegenError> {
+        self.block.class_decl(name, superclass, body)?;
+        Ok(self)
+    }
+
+    /// Add an export of the given declaration to the block.
+    pub fn export(mut self, declaration: impl Into<Statement>) -> Self {
+        self.block.export(declaration);
+        self
+    }
+
+    /// Add a default export of the given declaration to the block.
+    pub fn export_default(mut self, declaration: impl Into<Statement>) -> Self {
+        self.block.export_default(declaration);
+        self
+    }
+
+    /// Add a line comment to the block.
+    pub fn line_comment(mut self, text: &str) -> Self {
+        self.block.line_comment(text);
+        self
+    }
+
+    /// Add a block comment to the block.
+    pub fn block_comment(mut self, text: &str) -> Self {
+        self.block.block_comment(text);
+        self
+    }
+
+    /// Finish building the block.
+    pub fn build(self) -> Block {
+        self.block
+    }
+}
+
+impl std::fmt::Display for Block {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        display_generated(f, self.generate())
+    }
+}
+
+impl std::fmt::Display for VarType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codegen::{CodegenError, CodegenOptions, IndentStyle, SemicolonMode};
+    use crate::module::block::{ArrayPattern, Block, BlockContext, DestructurePattern, ExportSpecifier, ObjectPattern, Statement, VarType};
+    use crate::module::class::ClassMember;
+
+    #[test]
+    fn test_raw_stmt() {
+        let mut block = Block::new(0);
+        block.stmt(Statement::Raw("foo".to_string())).unwrap();
+        assert_eq!(block.generate().unwrap(), "foo\n");
+    }
+
+    #[test]
+    fn test_var_decl_stmt() {
+        let mut block = Block::new(0);
+        block.stmt(Statement::var_decl(VarType::Let, "foo", None)).unwrap();
+        assert_eq!(block.generate().unwrap(), "let foo;\n");
+    }
+
+    #[test]
+    fn test_var_decl_with_initializer_stmt() {
+        let mut block = Block::new(0);
+        block.var_decl(VarType::Let, "foo", Some(5.into())).unwrap();
+        assert_eq!(block.generate().unwrap(), "let foo = 5;\n");
+    }
+
+    #[test]
+    fn test_assign_stmt() {
+        let mut block = Block::new(0);
+        block.assign("foo", "??=", 42);
+        assert_eq!(block.generate().unwrap(), "foo ??= 42;\n");
+    }
+
+    #[test]
+    fn test_quoted_string_literals() {
+        assert_eq!(Statement::from_str_double_quoted("it's \"ok\"").generate().unwrap(), "\"it's \\\"ok\\\"\"");
+        assert_eq!(Statement::from_str_single_quoted("it's \"ok\"").generate().unwrap(), "'it\\'s \"ok\"'");
+        assert_eq!(Statement::from_str_single_quoted("a\\b\n").generate().unwrap(), "'a\\\\b\\n'");
+    }
+
+    #[test]
+    fn test_export_stmts() {
+        let mut block = Block::new(0);
+        block.export(Statement::var_decl(VarType::Const, "foo", Some(42.into())));
+        block.export_default(Statement::Raw("bar".to_string()));
+        block.stmt(Statement::ExportList {
+            names: vec![ExportSpecifier::new("foo"), ExportSpecifier::aliased("bar", "baz")],
+            from_path: None
+        }).unwrap();
+        block.stmt(Statement::ExportList {
+            names: vec![ExportSpecifier::new("x")],
+            from_path: Some("./x.js".to_string())
+        }).unwrap();
+
+        assert_eq!(
+            block.generate().unwrap(),
+            "export const foo = 42;\nexport default bar;\nexport { foo, bar as baz };\nexport { x } from './x.js';\n"
+        );
+    }
+
+    #[test]
+    fn test_renamed_re_export() {
+        let mut block = Block::new(0);
+        block.export_list(vec![ExportSpecifier::aliased("foo", "bar")], Some("./baz"));
+        block.export_list(vec![ExportSpecifier::aliased("foo", "default"), ExportSpecifier::new("bar")], Some("./baz"));
+        block.export_list(vec![ExportSpecifier::aliased("qux", "quux")], None);
+        assert_eq!(
+            block.generate().unwrap(),
+            concat!(
+                "export { foo as bar } from './baz';\n",
+                "export { foo as default, bar } from './baz';\n",
+                "export { qux as quux };\n"
+            )
+        );
+        assert_eq!(
+            block.generate_with_options(&CodegenOptions::new().with_minify(true)).unwrap(),
+            "export{foo as bar}from'./baz';export{foo as default,bar}from'./baz';export{qux as quux};"
+        );
+    }
+
+    #[test]
+    fn test_import_meta_stmt() {
+        let mut block = Block::new(0);
+        block.var_decl(VarType::Const, "meta", Some(Statement::ImportMeta)).unwrap();
+        assert_eq!(block.generate().unwrap(), "const meta = import.meta;\n");
+    }
+
+    #[test]
+    fn test_use_server_directive() {
+        let mut block = Block::new(1);
+        block.raw("foo()");
+        block.add_use_server();
+        assert_eq!(block.generate().unwrap(), "    \"use server\";\n    foo()\n");
+    }
+
+    #[test]
+    fn test_debugger_stmt() {
+        let mut block = Block::new(0);
+        block.debugger();
+        assert_eq!(block.generate().unwrap(), "debugger;\n");
+    }
+
+    #[test]
+    fn test_export_default_class() {
+        let mut block = Block::new(0);
+        block.export_default(Statement::ClassDecl {
+            name: "Foo".to_string(),
+            superclass: None,
+            body: Vec::new()
+        });
+        block.stmt(Statement::Export { declaration: Box::new(Statement::Identifier("foo".to_string())), is_default: true }).unwrap();
+        assert_eq!(block.generate().unwrap(), "export default class Foo {}\nexport default foo;\n");
+        let export = Statement::Export { declaration: Box::new(Statement::Identifier("foo".to_string())), is_default: true };
+        assert_eq!(Statement::export_default(Statement::Identifier("foo".to_string())), export);
+        assert_eq!(Statement::ExportDefault(Box::new(Statement::Identifier("foo".to_string()))), export);
+    }
+
+    #[test]
+    fn test_labeled_stmt() {
+        let mut block = Block::new(1);
+        block.labeled_stmt(
+            "outer",
+            Statement::Raw("for (const row of rows) { for (const cell of row) { if (cell) break outer } }".to_string())
+        );
+        assert_eq!(
+            block.generate().unwrap(),
+            "    outer:\n        for (const row of rows) { for (const cell of row) { if (cell) break outer } }\n"
+        );
+    }
+
+    #[test]
+    fn test_labeled_stmt_with_reserved_label() {
+        let result = Statement::Labeled {
+            label: "for".to_string(),
+            body: Box::new(Statement::Raw("foo()".to_string()))
+        }.generate();
+        assert!(matches!(result, Err(CodegenError::InvalidIdentifier(label)) if label == "for"));
+    }
+
+    #[test]
+    fn test_from_bool() {
+        assert_eq!(Statement::from(true), Statement::Literal { value: "true".to_string() });
+        assert_eq!(Statement::from(false), Statement::Literal { value: "false".to_string() });
+    }
+
+    #[test]
+    fn test_from_i64() {
+        assert_eq!(Statement::from(-9_007_199_254_740_991i64).generate().unwrap(), "-9007199254740991");
+    }
+
+    #[test]
+    fn test_from_u32() {
+        assert_eq!(Statement::from(42u32).generate().unwrap(), "42");
+    }
+
+    #[test]
+    fn test_from_u64() {
+        assert_eq!(Statement::from(9_007_199_254_740_991u64).generate().unwrap(), "9007199254740991");
+    }
+
+    #[test]
+    fn test_from_usize() {
+        assert_eq!(Statement::from(7usize).generate().unwrap(), "7");
+    }
+
+    #[test]
+    fn test_from_f64() {
+        assert_eq!(Statement::from(0.5f64).generate().unwrap(), "0.5");
+        assert_eq!(Statement::from(f64::INFINITY).generate().unwrap(), "Infinity");
+        assert_eq!(Statement::from(f64::NEG_INFINITY).generate().unwrap(), "-Infinity");
+        assert_eq!(Statement::from(f64::NAN).generate().unwrap(), "NaN");
+        assert_eq!(Statement::from(f64::NEG_INFINITY).member("toString").generate().unwrap(), "(-Infinity).toString");
+    }
+
+    #[test]
+    fn test_from_f32() {
+        assert_eq!(Statement::from(0.1f32).generate().unwrap(), "0.1");
+        assert_eq!(Statement::from(f32::INFINITY).generate().unwrap(), "Infinity");
+        assert_eq!(Statement::from(f32::NEG_INFINITY).generate().unwrap(), "-Infinity");
+        assert_eq!(Statement::from(f32::NAN).generate().unwrap(), "NaN");
+    }
+
+    #[test]
+    #[cfg(feature = "benchmarking")]
+    fn test_generate_benchmark() {
+        let mut block = Block::new(0);
+        block.raw("foo");
+        let (code, _) = block.generate_benchmark().unwrap();
+        assert_eq!(code, block.generate().unwrap());
+    }
+
+    #[test]
+    fn test_special_values() {
+        let mut block = Block::new(0);
+        block.null().undefined().nan().infinity();
+        assert_eq!(block.generate().unwrap(), "null;\nundefined;\nNaN;\nInfinity;\n");
+    }
+
+    #[test]
+    fn test_from_option() {
+        assert_eq!(Statement::from(None::<i32>), Statement::Null);
+        assert_eq!(Statement::from(Some(42)), Statement::from(42));
+    }
+
+    #[test]
+    fn test_regex_literal() {
+        let mut block = Block::new(0);
+        block.regex("https?://[a-z]+", "gi");
+        block.regex("a\\/b\\\\/c", "");
+        assert_eq!(block.generate().unwrap(), "/https?:\\/\\/[a-z]+/gi;\n/a\\/b\\\\\\/c/;\n");
+    }
+
+    #[test]
+    fn test_regex_literal_with_invalid_flag() {
+        let result = Statement::RegexLiteral { pattern: "foo".to_string(), flags: "gx".to_string() }.generate();
+        assert!(matches!(result, Err(CodegenError::InvalidStatement { context }) if context.contains("Invalid regex flag `x`")));
+    }
+
+    #[test]
+    fn test_from_i128() {
+        assert_eq!(Statement::from(9_007_199_254_740_993i128).generate().unwrap(), "9007199254740993n");
+    }
+
+    #[test]
+    fn test_numeric_literal_formats() {
+        assert_eq!(Statement::numeric_hex(255).generate().unwrap(), "0xFF");
+        assert_eq!(Statement::numeric_octal(63).generate().unwrap(), "0o77");
+        assert_eq!(Statement::numeric_binary(10).generate().unwrap(), "0b1010");
+        assert_eq!(Statement::numeric_with_separator(1_000_000, true).generate().unwrap(), "1_000_000");
+        assert_eq!(Statement::numeric_with_separator(-12_345, true).generate().unwrap(), "-12_345");
+        assert_eq!(Statement::numeric_with_separator(999, true).generate().unwrap(), "999");
+        assert_eq!(Statement::numeric_with_separator(1_000_000, false).generate().unwrap(), "1000000");
+    }
+
+    #[test]
+    fn test_from_str_escapes_quotes_and_backslashes() {
+        assert_eq!(Statement::from("it's").generate().unwrap(), r"'it\'s'");
+        assert_eq!(Statement::from("say \"hi\"").generate().unwrap(), r#"'say "hi"'"#);
+        assert_eq!(Statement::from(r"C:\dir").generate().unwrap(), r"'C:\\dir'");
+    }
+
+    #[test]
+    fn test_from_str_escapes_control_characters() {
+        assert_eq!(Statement::from("a\nb\rc\td").generate().unwrap(), r"'a\nb\rc\td'");
+        assert_eq!(Statement::from("a\0b").generate().unwrap(), r"'a\0b'");
+        assert_eq!(Statement::from("a\u{0}1").generate().unwrap(), r"'a\x001'");
+        assert_eq!(Statement::from("bell\u{7}").generate().unwrap(), r"'bell\u0007'");
+        assert_eq!(Statement::from("a\u{2028}b").generate().unwrap(), r"'a\u2028b'");
+    }
+
+    #[test]
+    fn test_from_str_escapes_astral_characters() {
+        assert_eq!(Statement::from("zażółć").generate().unwrap(), "'zażółć'");
+        assert_eq!(Statement::from("smile 😀".to_string()).generate().unwrap(), r"'smile \u{1f600}'");
+    }
+
+    #[test]
+    fn test_conditional_chain() {
+        let mut block = Block::new(0);
+        block.conditional_chain(
+            vec![Statement::Raw("a".to_string()), Statement::Raw("a.b".to_string())],
+            Statement::Raw("a.b()".to_string())
+        );
+        assert_eq!(block.generate().unwrap(), "a && a.b && a.b();\n");
+    }
+
+    #[test]
+    fn test_semicolon_modes() {
+        let mut block = Block::new(0);
+        block.var_decl(VarType::Let, "foo", Some(1.into())).unwrap();
+        block.raw("bar()");
+        block.stmt(Statement::Block(Box::new(Block::new(1)))).unwrap();
+        block.assign("foo", "=", 2);
+        block.class_decl("Foo", None, Vec::new()).unwrap();
+
+        let options = CodegenOptions::new().with_semicolons(SemicolonMode::Always);
+        assert_eq!(block.generate_with_options(&options).unwrap(), "let foo = 1;\nbar();\nfoo = 2;\nclass Foo {};\n");
+
+        let options = CodegenOptions::new().with_semicolons(SemicolonMode::Never);
+        assert_eq!(block.generate_with_options(&options).unwrap(), "let foo = 1\nbar()\nfoo = 2\nclass Foo {}\n");
+
+        let options = CodegenOptions::new().with_semicolons(SemicolonMode::AsNeeded);
+        assert_eq!(block.generate_with_options(&options).unwrap(), "let foo = 1;\nbar()\nfoo = 2;\nclass Foo {}\n");
+    }
+
+    #[test]
+    fn test_generate_as_async_iife() {
+        let mut block = Block::new(0);
+        block.push(Statement::var_decl(VarType::Const, "data", Some(Statement::Await(Box::new(Statement::Raw("load()".to_string()))))));
+        block.raw("console.log(data)");
+        assert_eq!(
+            block.generate_as_async_iife().unwrap(),
+            concat!(
+                "async function main() {\n",
+                "    const data = await load();\n",
+                "    console.log(data)\n",
+                "}\n",
+                "main().catch(console.error);\n"
+            )
+        );
+        let options = CodegenOptions::new().with_minify(true);
+        assert_eq!(
+            block.generate_as_async_iife_with_options(&options).unwrap(),
+            "async function main(){const data=await load();console.log(data);}main().catch(console.error);"
+        );
+
+        let mut generator = Block::new(0).with_context(BlockContext::Generator);
+        generator.stmt(Statement::Yield { value: Some(Box::new(1.into())), delegate: false }).unwrap();
+        assert!(matches!(generator.generate_as_async_iife(), Err(CodegenError::YieldOutsideGenerator)));
+    }
+
+    #[test]
+    fn test_indent_style() {
+        let mut block = Block::new(1);
+        block.class_decl("Foo", None, vec![ClassMember::field("x", None)]).unwrap();
+
+        let options = CodegenOptions::new().with_indent_style(IndentStyle::Tabs);
+        assert_eq!(block.generate_with_options(&options).unwrap(), "\tclass Foo {\n\t\tx;\n\t}\n");
+
+        let options = CodegenOptions::new().with_indent_style(IndentStyle::Spaces(2));
+        assert_eq!(block.generate_with_options(&options).unwrap(), "  class Foo {\n    x;\n  }\n");
+    }
+
+    #[test]
+    fn test_minify() {
+        let mut block = Block::new(1);
+        block.var_decl(VarType::Let, "x", Some(Statement::Binary {
+            left: Box::new(1.into()),
+            operator: "-".to_string(),
+            right: Box::new((-2).into())
+        })).unwrap();
+        block.assign("x", "+=", 3);
+        block.class_decl("Foo", Some(Statement::Raw("Bar".to_string())), vec![ClassMember::field("y", Some(4.into()))]).unwrap();
+        block.raw("console.log(x)");
+
+        let minified = block.generate_with_options(&CodegenOptions::new().with_minify(true)).unwrap();
+        assert_eq!(minified, "let x=1- -2;x+=3;class Foo extends Bar{y=4;};console.log(x);");
+        assert!(minified.len() < block.generate().unwrap().len());
+    }
+
+    #[test]
+    fn test_comments() {
+        let mut block = Block::new(1);
+        block.line_comment("Generated code");
+        block.block_comment("Inline");
+        block.block_comment("Adds two numbers.\n\n@param a first");
+        block.stmt(Statement::var_decl(VarType::Let, "x", Some(1.into())).with_comment("explanation")).unwrap();
+
+        assert_eq!(
+            block.generate().unwrap(),
+            concat!(
+                "    // Generated code\n",
+                "    /* Inline */\n",
+                "    /*\n",
+                "     * Adds two numbers.\n",
+                "     *\n",
+                "     * @param a first\n",
+                "     */\n",
+                "    let x = 1; // explanation\n"
+            )
+        );
+        assert_eq!(block.generate_with_options(&CodegenOptions::new().with_minify(true)).unwrap(), "let x=1;");
+    }
+
+    #[test]
+    fn test_display() {
+        let statement = Statement::var_decl(VarType::Const, "x", Some(1.into()));
+        assert_eq!(statement.to_string(), statement.generate().unwrap());
+        assert_eq!(format!("{} y", VarType::Let), "let y");
+
+        let mut block = Block::new(1);
+        block.stmt(statement).unwrap();
+        assert_eq!(format!("{}", block), block.generate().unwrap());
+
+        let invalid = Statement::Identifier("not valid".to_string());
+        assert_eq!(invalid.to_string(), "/* Invalid identifier `not valid` */");
+        block.stmt(Statement::TypeOf(Box::new(Statement::LineComment("*/".to_string())))).unwrap();
+        assert_eq!(block.to_string(), "/* Invalid statement: Expected expression, got LineComment(\"* /\") */");
+    }
+
+    #[test]
+    fn test_extend_block() {
+        let mut inner = Block::new(3);
+        inner.raw("bar()");
+        inner.class_decl("Foo", None, vec![ClassMember::field("x", None)]).unwrap();
+
+        let mut block = Block::new(1);
+        block.raw("foo()");
+        block.extend(inner);
+        block.extend_stmts(vec![Statement::Raw("baz()".to_string())]);
+        assert_eq!(block.generate().unwrap(), "    foo()\n    bar()\n    class Foo {\n        x;\n    }\n    baz()\n");
+    }
+
+    #[test]
+    fn test_find_var_decls() {
+        let mut block = Block::new(0);
+        block.var_decl(VarType::Let, "foo", None).unwrap();
+        block.raw("bar");
+        assert_eq!(block.find_var_decls(), vec![(&VarType::Let, "foo")]);
+
+        let mut inner = Block::new(1);
+        inner.var_decl(VarType::Const, "baz", Some(1.into())).unwrap();
+        block.stmt(Statement::Block(Box::new(inner))).unwrap();
+        block.export(Statement::var_decl(VarType::Var, "qux", None));
+        assert_eq!(block.find_var_decls(), vec![(&VarType::Let, "foo"), (&VarType::Const, "baz"), (&VarType::Var, "qux")]);
+        assert!(block.declares("baz"));
+        assert!(!block.declares("bar"));
+    }
+
+    #[test]
+    fn test_sequence_initializers() {
+        let sequence = Statement::Sequence(vec![1.into(), 2.into()]);
+        let mut block = Block::new(0);
+        block.var_decl(VarType::Let, "x", Some(sequence.clone())).unwrap();
+        block.stmt(Statement::MultiVarDecl { var_type: VarType::Let, declarators: vec![("y".to_string(), Some(Box::new(sequence)))] }).unwrap();
+        assert_eq!(block.generate().unwrap(), "let x = (1, 2);\nlet y = (1, 2);\n");
+    }
+
+    #[test]
+    fn test_alpha_rename() {
+        let ident = |name: &str| Statement::Identifier(name.to_string());
+        let mut inner = Block::new(1);
+        inner.var_decl(VarType::Let, "count", Some(ident("count"))).unwrap();
+        let mut method_body = Block::new(2);
+        method_body.var_decl(VarType::Const, "total", Some(ident("count"))).unwrap();
+
+        let mut block = Block::new(0);
+        block.var_decl(VarType::Const, "count", Some(1.into())).unwrap();
+        block.var_decl(VarType::Let, "_a0", None).unwrap();
+        block.destructure(VarType::Const, DestructurePattern::Object(vec![ObjectPattern::new("total")]), ident("count"));
+        block.stmt(Statement::Block(Box::new(inner))).unwrap();
+        block.assign("_a0", "=", ident("total"));
+        block.class_decl("Foo", None, vec![ClassMember::method("get", vec![], method_body)]).unwrap();
+        block.export(Statement::var_decl(VarType::Const, "result", Some(ident("total"))));
+        block.export_list(vec![ExportSpecifier::new("count")], None);
+
+        let renames = block.alpha_rename("_a");
+        assert_eq!(renames.len(), 2);
+        assert_eq!(renames["count"], "_a1");
+        assert_eq!(renames["_a0"], "_a2");
+        assert_eq!(block.generate().unwrap(), concat!(
+            "const _a1 = 1;\n",
+            "let _a2;\n",
+            "const { total } = _a1;\n",
+            "    let _a1 = _a1;\n",
+            "_a2 = total;\n",
+            "class Foo {\n",
+            "    get() {\n",
+            "        const total = _a1;\n",
+            "    }\n",
+            "}\n",
+            "export const result = total;\n",
+            "export { _a1 as count };\n"
+        ));
+    }
+
+    #[test]
+    fn test_alpha_rename_rest_binding() {
+        let mut block = Block::new(0);
+        block.destructure(
+            VarType::Const,
+            DestructurePattern::Object(vec![ObjectPattern::new("a"), ObjectPattern::rest("rest")]),
+            Statement::Identifier("obj".to_string())
+        );
+        block.stmt(Statement::Identifier("rest".to_string()).call(vec![])).unwrap();
+
+        let renames = block.alpha_rename("_r");
+        assert_eq!(renames["rest"], "_r1");
+        assert_eq!(block.generate().unwrap(), "const { a: _r0, ..._r1 } = obj;\n_r1();\n");
+    }
+
+    #[test]
+    fn test_line_width() {
+        let ident = |name: &str| Statement::Identifier(name.to_string());
+        let args = ["first", "second", "third", "fourth", "fifth", "sixth"].map(ident).to_vec();
+        let call = ident("configure").call(args);
+        let mut block = Block::new(1);
+        block.var_decl(VarType::Const, "x", Some(call.clone())).unwrap();
+
+        let options = CodegenOptions::new().with_line_width(Some(40));
+        assert_eq!(block.generate_with_options(&options).unwrap(), concat!(
+            "    const x = configure(\n",
+            "        first,\n",
+            "        second,\n",
+            "        third,\n",
+            "        fourth,\n",
+            "        fifth,\n",
+            "        sixth\n",
+            "    );\n"
+        ));
+        let options = CodegenOptions::new().with_line_width(Some(80));
+        assert_eq!(
+            block.generate_with_options(&options).unwrap(),
+            "    const x = configure(first, second, third, fourth, fifth, sixth);\n"
+        );
+        let options = CodegenOptions::new().with_line_width(Some(40)).with_minify(true);
+        assert_eq!(call.generate_with_options(&options).unwrap(), "configure(first,second,third,fourth,fifth,sixth)");
+    }
+
+    #[test]
+    fn test_with_indent() {
+        let mut nested = Block::new(1);
+        nested.raw("bar()");
+        let mut block = Block::new(0);
+        block.raw("foo()");
+        block.stmt(Statement::Block(Box::new(nested))).unwrap();
+
+        let block = block.with_indent(2);
+        assert_eq!(block.indent, 2);
+        assert_eq!(block.generate().unwrap(), "        foo()\n            bar()\n");
+        match &block.statements[1] {
+            Statement::Block(nested) => assert_eq!(nested.generate().unwrap(), "            bar()\n"),
+            statement => panic!("Expected a nested block, got {:?}", statement)
+        }
+    }
+
+    #[test]
+    fn test_with_indent_recursive() {
+        let mut labeled_body = Block::new(1);
+        labeled_body.raw("bar()");
+        let mut method_body = Block::new(5);
+        method_body.raw("baz()");
+        let mut block = Block::new(0);
+        block.labeled_stmt("outer", Statement::Block(Box::new(labeled_body)));
+        block.class_decl("Foo", None, vec![ClassMember::method("run", Vec::new(), method_body)]).unwrap();
+
+        let block = block.with_indent(2);
+        match &block.statements[0] {
+            Statement::Labeled { body, .. } => match &**body {
+                Statement::Block(nested) => assert_eq!(nested.generate().unwrap(), "            bar()\n"),
+                statement => panic!("Expected a nested block, got {:?}", statement)
+            },
+            statement => panic!("Expected a labeled statement, got {:?}", statement)
+        }
+        match &block.statements[1] {
+            Statement::ClassDecl { body, .. } => match &body[0] {
+                ClassMember::Method { body, .. } => assert_eq!(body.indent, 7),
+                member => panic!("Expected a method, got {:?}", member)
+            },
+            statement => panic!("Expected a class declaration, got {:?}", statement)
+        }
+    }
+
+    #[test]
+    fn test_binary_precedence() {
+        let binary = |left: Statement, operator: &str, right: Statement| Statement::Binary {
+            left: Box::new(left),
+            operator: operator.to_string(),
+            right: Box::new(right)
+        };
+
+        assert_eq!(binary(binary(1.into(), "+", 2.into()), "*", 3.into()).generate().unwrap(), "(1 + 2) * 3");
+        assert_eq!(binary(1.into(), "+", binary(2.into(), "*", 3.into())).generate().unwrap(), "1 + 2 * 3");
+        assert_eq!(binary(binary(1.into(), "-", 2.into()), "-", 3.into()).generate().unwrap(), "1 - 2 - 3");
+        assert_eq!(binary(1.into(), "-", binary(2.into(), "-", 3.into())).generate().unwrap(), "1 - (2 - 3)");
+        assert_eq!(binary(2.into(), "**", binary(3.into(), "**", 2.into())).generate().unwrap(), "2 ** 3 ** 2");
+        assert_eq!(binary((-2).into(), "**", 2.into()).generate().unwrap(), "(-2) ** 2");
+        assert_eq!(
+            binary(binary(Statement::Raw("a".to_string()), "&&", Statement::Raw("b".to_string())), "??", Statement::Raw("c".to_string())).generate().unwrap(),
+            "(a && b) ?? c"
+        );
+        assert_eq!(binary(Statement::Raw("x + 1".to_string()), "*", Statement::Raw("f(x + 1)".to_string())).generate().unwrap(), "(x + 1) * f(x + 1)");
+        assert_eq!(Statement::Await(Box::new(binary(1.into(), "+", 2.into()))).generate().unwrap(), "await (1 + 2)");
+    }
+
+    #[test]
+    fn test_generate_errors() {
+        let mut block = Block::new(0);
+        assert!(matches!(block.literal(Statement::Null), Err(CodegenError::InvalidStatement { .. })));
+        assert!(block.literal(42).is_ok());
+
+        block.stmt(Statement::Binary {
+            left: Box::new(1.into()),
+            operator: "<>".to_string(),
+            right: Box::new(2.into())
+        }).unwrap();
+        assert!(matches!(block.generate(), Err(CodegenError::InvalidOperator(operator)) if operator == "<>"));
+
+        let assign = Statement::Assign { name: "x".to_string(), operator: "=+".to_string(), value: Box::new(1.into()) };
+        assert!(matches!(assign.generate(), Err(CodegenError::InvalidOperator(_))));
+    }
+
+    #[test]
+    fn test_invalid_declaration_names() {
+        let mut block = Block::new(0);
+        assert!(matches!(block.var_decl(VarType::Let, "static", None), Err(CodegenError::InvalidIdentifier(_))));
+        assert!(matches!(block.var_decl(VarType::Const, "1st", None), Err(CodegenError::InvalidIdentifier(_))));
+        assert!(matches!(block.class_decl("interface", None, Vec::new()), Err(CodegenError::InvalidIdentifier(_))));
+        assert!(block.statements.is_empty());
+    }
+
+    #[test]
+    fn test_check_duplicate_decls() {
+        let mut block = Block::new(0);
+        block.var_decl(VarType::Let, "x", None).unwrap();
+        block.var_decl(VarType::Var, "y", None).unwrap();
+        block.var_decl(VarType::Var, "y", None).unwrap();
+        assert!(block.check_duplicate_decls().is_empty());
+
+        block.var_decl(VarType::Let, "x", Some(1.into())).unwrap();
+        block.var_decl(VarType::Const, "y", None).unwrap();
+        block.var_decl(VarType::Const, "x", None).unwrap();
+        let mut nested = Block::new(1);
+        nested.var_decl(VarType::Let, "x", None).unwrap();
+        block.stmt(Statement::Block(Box::new(nested))).unwrap();
+        assert_eq!(block.check_duplicate_decls(), vec!["x".to_string(), "y".to_string()]);
+    }
+
+    #[test]
+    fn test_statement_classifiers() {
+        let declaration = Statement::var_decl(VarType::Let, "x", None);
+        assert!(declaration.is_declaration() && !declaration.is_expression());
+        assert!(Statement::from(1).is_expression() && !Statement::from(1).is_declaration());
+        assert!(!Statement::Debugger.is_expression() && !Statement::Debugger.is_declaration());
+
+        let nested_declaration = Statement::var_decl(VarType::Const, "y", Some(declaration.clone()));
+        assert!(matches!(nested_declaration.generate(), Err(CodegenError::InvalidStatement { .. })));
+        assert!(matches!(Statement::Await(Box::new(Statement::Debugger)).generate(), Err(CodegenError::InvalidStatement { .. })));
+        let export = Statement::Export { declaration: Box::new(1.into()), is_default: false };
+        assert!(matches!(export.generate(), Err(CodegenError::InvalidStatement { .. })));
+    }
+
+    #[test]
+    fn test_walk() {
+        let binary = Statement::Binary {
+            left: Box::new(1.into()),
+            operator: "+".to_string(),
+            right: Box::new(2.into())
+        };
+        let mut visits = 0;
+        binary.walk(|_| visits += 1);
+        assert_eq!(visits, 3);
+
+        let mut block = Block::new(0);
+        block.stmt(binary).unwrap();
+        block.class_decl("Foo", None, vec![ClassMember::field("x", Some(3.into()))]).unwrap();
+        block.walk_mut(|statement| {
+            if let Statement::Literal { value } = statement {
+                *value = format!("{}0", value);
+            }
+        });
+        assert_eq!(block.generate().unwrap(), "10 + 20;\nclass Foo {\n    x = 30;\n}\n");
+    }
+
+    #[test]
+    fn test_collect_declarations_and_identifiers() {
+        let call = Statement::Identifier("foo".to_string()).call(vec![
+            Statement::Identifier("bar".to_string()),
+            Statement::Identifier("foo".to_string())
+        ]);
+        let export = Statement::Export { declaration: Box::new(Statement::var_decl(VarType::Const, "x", Some(call))), is_default: false };
+        assert_eq!(export.collect_declarations(), vec!["x"]);
+        assert_eq!(export.collect_identifiers(), vec!["foo", "bar"]);
+
+        let class = Statement::ClassDecl {
+            name: "Foo".to_string(),
+            superclass: Some(Box::new(Statement::Identifier("Base".to_string()))),
+            body: vec![ClassMember::field("x", Some(Statement::Identifier("y".to_string())))]
+        };
+        assert_eq!(class.collect_declarations(), vec!["Foo"]);
+        assert_eq!(class.collect_identifiers(), vec!["Base", "y"]);
+
+        let list = Statement::ExportList { names: vec![ExportSpecifier::aliased("a", "b")], from_path: None };
+        assert!(list.collect_declarations().is_empty());
+        assert_eq!(list.collect_identifiers(), vec!["a"]);
+    }
+
+    #[test]
+    fn test_to_js_string() {
+        let statement = Statement::var_decl(VarType::Let, "x", Some(1.into()));
+        assert_eq!(statement.to_js_string().unwrap(), "let x = 1");
+        let options = CodegenOptions::new().with_minify(true);
+        assert_eq!(statement.to_js_string_with_options(&options).unwrap(), "let x=1");
+
+        let mut block = Block::new(1);
+        block.stmt(statement).unwrap();
+        assert_eq!(block.to_js_string().unwrap(), "    let x = 1;\n");
+    }
+
+    #[test]
+    fn test_block_builder() {
+        let built = Block::builder(1)
+            .line_comment("setup")
+            .var_decl(VarType::Const, "x", Some(1.into())).unwrap()
+            .raw("console.log(x)")
+            .stmts(vec![Statement::Null])
+            .export(Statement::Raw("function foo() {}".to_string()))
+            .build();
+
+        let mut block = Block::new(1);
+        block.line_comment("setup");
+        block.var_decl(VarType::Const, "x", Some(1.into())).unwrap();
+        block.raw("console.log(x)");
+        block.extend_stmts(vec![Statement::Null]);
+        block.export(Statement::Raw("function foo() {}".to_string()));
+
+        assert_eq!(built, block);
+        assert!(Block::builder(0).var_decl(VarType::Let, "class", None).is_err());
+    }
+
+    #[test]
+    fn test_sub_block() {
+        let mut block = Block::new(1);
+        block.raw("if (x) {");
+        let mut body = block.sub_block();
+        body.raw("foo()");
+        let mut inner = body.sub_block();
+        inner.raw("bar()");
+        body.push_block(inner);
+        block.push_block(body);
+        block.raw("}");
+
+        assert_eq!(
+            block.generate().unwrap(),
+            concat!(
+                "    if (x) {\n",
+                "        foo()\n",
+                "            bar()\n",
+                "    }\n"
+            )
+        );
+        // Nested blocks keep their relative indentation when the parent is generated at another level.
+        assert_eq!(
+            block.generate_indented(0, &CodegenOptions::default()).unwrap(),
+            "if (x) {\n    foo()\n        bar()\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_expression_statement() {
+        let mut block = Block::new(0);
+        block.expr_stmt(Statement::Raw("foo()".to_string()));
+        block.expr_stmt(Statement::Assign { name: "x".to_string(), operator: "=".to_string(), value: Box::new(5.into()) });
+        block.expr_stmt(Statement::ClassDecl { name: "Foo".to_string(), superclass: None, body: Vec::new() });
+        assert_eq!(block.generate().unwrap(), "foo();\nx = 5;\n(class Foo {});\n");
+
+        let options = CodegenOptions::new().with_semicolons(SemicolonMode::Never);
+        assert_eq!(block.generate_with_options(&options).unwrap(), "foo()\nx = 5\n;(class Foo {})\n");
+        for (expr, code) in [("[1].map(f)", ";[1].map(f)"), ("`a`.trim()", ";`a`.trim()"), ("-x", ";-x"), ("/a/.test(b)", ";/a/.test(b)")] {
+            let statement = Statement::ExpressionStatement(Box::new(Statement::Raw(expr.to_string())));
+            assert_eq!(statement.generate_with_options(&options).unwrap(), code);
+        }
+        let options = CodegenOptions::new().with_minify(true);
+        assert_eq!(block.generate_with_options(&options).unwrap(), "foo();x=5;(class Foo{});");
+
+        assert_eq!(Statement::ExpressionStatement(Box::new(1.into())).generate().unwrap(), "1;");
+        assert!(Statement::ExpressionStatement(Box::new(Statement::Debugger)).generate().is_err());
+    }
+
+    #[test]
+    fn test_sequence() {
+        let assign = |name: &str, value: i32| Statement::Assign { name: name.to_string(), operator: "=".to_string(), value: Box::new(value.into()) };
+        let sequence = Statement::Sequence(vec![assign("x", 1), assign("y", 2)]);
+        assert_eq!(sequence.generate().unwrap(), "x = 1, y = 2");
+        assert_eq!(sequence.generate_with_options(&CodegenOptions::new().with_minify(true)).unwrap(), "x=1,y=2");
+
+        let binary = Statement::Binary { operator: "+".to_string(), left: Box::new(sequence.clone()), right: Box::new(1.into()) };
+        assert_eq!(binary.generate().unwrap(), "(x = 1, y = 2) + 1");
+        let nested = Statement::Sequence(vec![Statement::Raw("a ? b : c".to_string()), sequence.clone()]);
+        assert_eq!(nested.generate().unwrap(), "(a ? b : c), (x = 1, y = 2)");
+
+        let mut block = Block::new(0);
+        block.sequence(vec![Statement::Raw("foo()".to_string()), Statement::Raw("bar()".to_string())]);
+        block.export_default(sequence.clone());
+        block.class_decl("Foo", Some(sequence), Vec::new()).unwrap();
+        assert_eq!(block.generate().unwrap(), "foo(), bar();\nexport default (x = 1, y = 2);\nclass Foo extends (x = 1, y = 2) {}\n");
+        assert!(Statement::Sequence(Vec::new()).generate().is_err());
+        assert!(Statement::Sequence(vec![Statement::Debugger]).generate().is_err());
+    }
+
+    #[test]
+    fn test_instanceof_and_in() {
+        let instanceof = Statement::Instanceof {
+            left: Box::new(Statement::Raw("x".to_string())),
+            right: Box::new(Statement::Raw("Date".to_string()))
+        };
+        assert_eq!(instanceof.generate().unwrap(), "x instanceof Date");
+        let in_expr = Statement::InExpr { left: Box::new("key".into()), right: Box::new(Statement::Raw("obj".to_string())) };
+        assert_eq!(in_expr.generate().unwrap(), "'key' in obj");
+
+        let both = Statement::Binary { left: Box::new(instanceof), operator: "&&".to_string(), right: Box::new(in_expr.clone()) };
+        assert_eq!(both.generate().unwrap(), "x instanceof Date && 'key' in obj");
+        let nested = Statement::Instanceof { left: Box::new(Statement::Raw("a".to_string())), right: Box::new(in_expr) };
+        assert_eq!(nested.generate().unwrap(), "a instanceof ('key' in obj)");
+        let minified = CodegenOptions::new().with_minify(true);
+        assert_eq!(nested.generate_with_options(&minified).unwrap(), "a instanceof('key'in obj)");
+    }
+
+    #[test]
+    fn test_typeof_and_void() {
+        let type_of = Statement::TypeOf(Box::new(Statement::Identifier("undeclaredVar".to_string())));
+        assert_eq!(type_of.generate().unwrap(), "typeof undeclaredVar");
+        assert_eq!(Statement::VoidExpr(Box::new(0.into())).generate().unwrap(), "void 0");
+
+        let comparison = Statement::Binary { left: Box::new(type_of), operator: "===".to_string(), right: Box::new("string".into()) };
+        assert_eq!(comparison.generate().unwrap(), "typeof undeclaredVar === 'string'");
+        let sum = Statement::Binary { left: Box::new(1.into()), operator: "+".to_string(), right: Box::new(2.into()) };
+        assert_eq!(Statement::VoidExpr(Box::new(sum)).generate().unwrap(), "void (1 + 2)");
+        assert_eq!(Statement::TypeOf(Box::new(Statement::TypeOf(Box::new(1.into())))).generate().unwrap(), "typeof typeof 1");
+        assert!(Statement::TypeOf(Box::new(Statement::Debugger)).generate().is_err());
+    }
+
+    #[test]
+    fn test_identifier() {
+        let foo = Statement::Identifier("foo".to_string());
+        assert_eq!(foo.generate().unwrap(), "foo");
+        let sum = Statement::Binary { left: Box::new(foo.clone()), operator: "*".to_string(), right: Box::new(2.into()) };
+        assert_eq!(sum.generate().unwrap(), "foo * 2");
+        assert_eq!(Statement::Await(Box::new(foo)).generate().unwrap(), "await foo");
+
+        for name in ["this", "1st", "foo.bar", ""] {
+            let result = Statement::Identifier(name.to_string()).generate();
+            assert!(matches!(result, Err(CodegenError::InvalidIdentifier(invalid)) if invalid == name));
+        }
+    }
+
+    #[test]
+    fn test_index() {
+        let index = |object: Statement, index: Statement| Statement::Index { object: Box::new(object), index: Box::new(index) };
+        assert_eq!(index(Statement::Identifier("arr".to_string()), 0.into()).generate().unwrap(), "arr[0]");
+        assert_eq!(index(Statement::Identifier("obj".to_string()), "key".into()).generate().unwrap(), "obj['key']");
+
+        let sum = Statement::Binary { left: Box::new(1.into()), operator: "+".to_string(), right: Box::new(2.into()) };
+        assert_eq!(index(sum.clone(), sum.clone()).generate().unwrap(), "(1 + 2)[1 + 2]");
+        let matrix = index(index(Statement::Identifier("matrix".to_string()), Statement::Identifier("i".to_string())), 1.into());
+        assert_eq!(matrix.generate().unwrap(), "matrix[i][1]");
+        assert!(index(Statement::Identifier("arr".to_string()), Statement::Debugger).generate().is_err());
+    }
+
+    #[test]
+    fn test_member_access_and_call() {
+        let ident = |name: &str| Statement::Identifier(name.to_string());
+        let chained = ident("arr")
+            .method_call("filter", vec![ident("pred")])
+            .method_call("map", vec![ident("mapper")])
+            .member("length");
+        assert_eq!(chained.generate().unwrap(), "arr.filter(pred).map(mapper).length");
+
+        let sum = Statement::Binary { left: Box::new(1.into()), operator: "+".to_string(), right: Box::new(2.into()) };
+        let sequence = Statement::Sequence(vec![ident("a"), ident("b")]);
+        let call = sum.clone().member("toFixed").call(vec![sum, sequence, Statement::Raw("a ? b : c".to_string())]);
+        assert_eq!(call.generate().unwrap(), "(1 + 2).toFixed(1 + 2, (a, b), (a ? b : c))");
+        assert_eq!(call.generate_with_options(&CodegenOptions::new().with_minify(true)).unwrap(), "(1+2).toFixed(1+2,(a,b),(a ? b : c))");
+        assert_eq!(Statement::from(1).member("toString").call(Vec::new()).generate().unwrap(), "(1).toString()");
+        assert_eq!(Statement::from(1.5).member("toFixed").call(Vec::new()).generate().unwrap(), "1.5.toFixed()");
+        assert!(ident("foo").call(vec![Statement::Debugger]).generate().is_err());
+    }
+
+    #[test]
+    fn test_multi_var_decl() {
+        let mut block = Block::new(0);
+        block.multi_var_decl(VarType::Let, vec![
+            ("i", Some(0.into())),
+            ("j", Some(Statement::Binary {
+                left: Box::new(Statement::Identifier("n".to_string())),
+                operator: "-".to_string(),
+                right: Box::new(1.into())
+            })),
+            ("k", None),
+        ]).unwrap();
+        assert_eq!(block.generate().unwrap(), "let i = 0, j = n - 1, k;\n");
+        assert_eq!(block.generate_with_options(&CodegenOptions::new().with_minify(true)).unwrap(), "let i=0,j=n-1,k;");
+        assert_eq!(block.find_var_decls(), vec![(&VarType::Let, "i"), (&VarType::Let, "j"), (&VarType::Let, "k")]);
+
+        block.var_decl(VarType::Let, "k", None).unwrap();
+        assert_eq!(block.check_duplicate_decls(), vec!["k".to_string()]);
+
+        let mut single = Block::new(0);
+        single.multi_var_decl(VarType::Const, vec![("x", Some(1.into()))]).unwrap();
+        let mut expected = Block::new(0);
+        expected.var_decl(VarType::Const, "x", Some(1.into())).unwrap();
+        assert_eq!(single.generate().unwrap(), expected.generate().unwrap());
+
+        assert!(matches!(block.multi_var_decl(VarType::Var, vec![("a", None), ("new", None)]), Err(CodegenError::InvalidIdentifier(_))));
+        let empty = Statement::MultiVarDecl { var_type: VarType::Var, declarators: Vec::new() };
+        assert!(empty.generate().is_err());
+    }
+
+    #[test]
+    fn test_destructure_decl() {
+        let mut block = Block::new(0);
+        block.destructure(
+            VarType::Const,
+            DestructurePattern::Object(vec![
+                ObjectPattern::aliased("a", "renamed").with_default(Statement::Identifier("fallback".to_string())),
+                ObjectPattern::new("b"),
+                ObjectPattern::rest("others"),
+            ]),
+            Statement::Identifier("obj".to_string())
+        );
+        block.destructure(
+            VarType::Let,
+            DestructurePattern::Array(vec![ArrayPattern::new("first"), ArrayPattern::hole(), ArrayPattern::rest("rest")]),
+            Statement::Identifier("arr".to_string())
+        );
+        assert_eq!(
+            block.generate().unwrap(),
+            "const { a: renamed = fallback, b, ...others } = obj;\nlet [first, , ...rest] = arr;\n"
+        );
+        assert_eq!(
+            block.generate_with_options(&CodegenOptions::new().with_minify(true)).unwrap(),
+            "const{a:renamed=fallback,b,...others}=obj;let[first,,...rest]=arr;"
+        );
+        assert_eq!(
+            block.find_var_decls(),
+            vec![(&VarType::Const, "renamed"), (&VarType::Const, "b"), (&VarType::Const, "others"), (&VarType::Let, "first"), (&VarType::Let, "rest")]
+        );
+
+        let pattern = DestructurePattern::Array(vec![ArrayPattern::new("x").with_default(1), ArrayPattern::hole()]);
+        assert_eq!(pattern.generate_indented(0, &CodegenOptions::default()).unwrap(), "[x = 1, ,]");
+        assert_eq!(DestructurePattern::Object(Vec::new()).generate_indented(0, &CodegenOptions::default()).unwrap(), "{}");
+
+        let invalid = [
+            DestructurePattern::Array(vec![ArrayPattern::rest("rest"), ArrayPattern::new("last")]),
+            DestructurePattern::Object(vec![ObjectPattern::rest("rest").with_default(1)]),
+            DestructurePattern::Object(vec![ObjectPattern::new("class")]),
+        ];
+        for pattern in invalid {
+            assert!(pattern.generate_indented(0, &CodegenOptions::default()).is_err());
+        }
+    }
+
+    #[test]
+    fn test_template_literals() {
+        let template = Statement::TemplateLiteral {
+            quasis: vec!["Hello ".to_string(), "! `${x}` costs $5\\".to_string()],
+            expressions: vec![Statement::Identifier("name".to_string())]
+        };
+        assert_eq!(template.generate().unwrap(), "`Hello ${name}! \\`\\${x}\\` costs $5\\\\`");
+
+        let mut block = Block::new(0);
+        block.tagged_template(Statement::Identifier("html".to_string()), Statement::TemplateLiteral {
+            quasis: vec!["<div>".to_string(), "</div>".to_string()],
+            expressions: vec![Statement::Identifier("content".to_string())]
+        });
+        block.tagged_template(Statement::Identifier("String".to_string()).member("raw"), Statement::TemplateLiteral {
+            quasis: vec!["a\nb".to_string()],
+            expressions: Vec::new()
+        });
+        assert_eq!(block.generate().unwrap(), "html`<div>${content}</div>`;\nString.raw`a\nb`;\n");
+
+        let invalid = Statement::TemplateLiteral { quasis: vec!["a".to_string()], expressions: vec![1.into()] };
+        assert!(invalid.generate().is_err());
+        let invalid = Statement::TaggedTemplate { tag: Box::new(Statement::Identifier("css".to_string())), template: Box::new("a".into()) };
+        assert!(invalid.generate().is_err());
+
+        let html = Statement::html_template(vec!["<div>", "</div>"], vec![Statement::Identifier("content".to_string())]);
+        assert_eq!(html.generate().unwrap(), "html`<div>${content}</div>`");
+        assert_eq!(Statement::css_template(vec!["color: red;"], Vec::new()).generate().unwrap(), "css`color: red;`");
+        let gql = Statement::gql_template(vec!["query { user(id: ", ") { name } }"], vec![Statement::Identifier("id".to_string())]);
+        assert_eq!(gql.generate().unwrap(), "gql`query { user(id: ${id}) { name } }`");
+    }
+
+    #[test]
+    fn test_block_context() {
+        let mut block = Block::new(0);
+        assert!(matches!(block.await_expr(Statement::Raw("load()".to_string())), Err(CodegenError::AwaitOutsideAsync)));
+        assert!(matches!(block.yield_stmt(Some(1.into()), false), Err(CodegenError::YieldOutsideGenerator)));
+        let nested = Statement::var_decl(VarType::Const, "x", Some(Statement::Await(Box::new(Statement::Raw("load()".to_string())))));
+        assert!(matches!(block.stmt(nested.clone()), Err(CodegenError::AwaitOutsideAsync)));
+        assert!(block.statements.is_empty());
+
+        let mut block = Block::new(0).with_context(BlockContext::AsyncFunction);
+        block.stmt(nested).unwrap();
+        assert!(block.yield_stmt(None, false).is_err());
+        let mut body = block.sub_block();
+        body.await_expr(Statement::Raw("flush()".to_string())).unwrap();
+        block.push_block(body);
+        assert_eq!(block.generate().unwrap(), "const x = await load();\n    await flush();\n");
+
+        let mut block = Block::builder(0).context(BlockContext::AsyncGenerator)
+            .stmt(Statement::Await(Box::new(Statement::Raw("load()".to_string())))).unwrap()
+            .stmt(Statement::Yield { value: None, delegate: false }).unwrap()
+            .build();
+        assert!(block.generate().is_ok());
+        block.context = BlockContext::Generator;
+        assert!(block.await_expr(Statement::Null).is_err());
+
+        // Statements added without `stmt` are checked when the block is generated.
+        let load = || Statement::Await(Box::new(Statement::Raw("load()".to_string())));
+        let mut block = Block::new(0);
+        block.assign("x", "=", load());
+        assert!(matches!(block.generate(), Err(CodegenError::AwaitOutsideAsync)));
+        assert!(block.generate_to(&mut Vec::new(), &CodegenOptions::default()).is_err());
+        assert!(block.generate_with_source_map(&CodegenOptions::default(), &mut crate::codegen::sourcemap::SourceMap::new()).is_err());
+        block.context = BlockContext::AsyncFunction;
+        assert_eq!(block.generate().unwrap(), "x = await load();\n");
+
+        let mut block = Block::new(0);
+        block.extend_stmts(vec![Statement::Yield { value: None, delegate: false }]);
+        assert!(matches!(block.generate(), Err(CodegenError::YieldOutsideGenerator)));
+
+        // Nested blocks are a part of the same function as their parent.
+        let mut inner = Block::new(1).with_context(BlockContext::AsyncFunction);
+        inner.await_expr(load()).unwrap();
+        let mut block = Block::new(0);
+        block.push_block(inner);
+        assert!(matches!(block.generate(), Err(CodegenError::AwaitOutsideAsync)));
+
+        let block = Block::builder(0).stmts(vec![load()]).build();
+        assert!(block.generate().is_err());
+        let block = Block::builder(0).context(BlockContext::AsyncFunction).stmts(vec![load()]).build();
+        assert!(block.generate().is_ok());
+    }
+
+    #[test]
+    fn test_hoist_var_decls() {
+        let mut block = Block::new(0);
+        block.raw("foo()");
+        block.var_decl(VarType::Var, "x", Some(1.into())).unwrap();
+        block.var_decl(VarType::Let, "y", Some(2.into())).unwrap();
+        block.var_decl(VarType::Var, "z", None).unwrap();
+        block.var_decl(VarType::Var, "x", Some(3.into())).unwrap();
+        block.hoist_var_decls();
+        assert_eq!(block.generate().unwrap(), "var x;\nvar z;\nfoo()\nx = 1;\nlet y = 2;\nx = 3;\n");
+    }
+}
@@ -0,0 +1,571 @@
+use super::block::{Block, BlockContext, Statement, ASSIGNMENT_PRECEDENCE};
+#[cfg(feature = "typescript")]
+use super::typescript::TsType;
+use super::validate::validate_identifier;
+use crate::codegen::{CodegenError, CodegenOptions};
+
+/// Member of a class body.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ClassMember {
+    /// Class method (including constructor, getters and setters).
+    Method {
+        /// Whether the method is static.
+        is_static: bool,
+        /// Whether the method is async.
+        is_async: bool,
+        /// Whether the method is a generator.
+        is_generator: bool,
+        /// Whether the method is private (`#name`).
+        is_private: bool,
+        /// The kind of the method.
+        kind: MethodKind,
+        /// The name of the method without the `#` prefix (ignored for constructors).
+        name: String,
+        /// The parameters of the method.
+        params: Vec<FunctionParam>,
+        /// The body of the method.
+        body: Box<Block>
+    },
+    /// Class field.
+    Field {
+        /// Whether the field is static.
+        is_static: bool,
+        /// Whether the field is private (`#name`).
+        is_private: bool,
+        /// The name of the field without the `#` prefix.
+        name: String,
+        /// Initial value of the field.
+        value: Option<Box<Statement>>
+    },
+    /// Auto-accessor field from the decorators proposal (eg. `accessor foo = 1`).
+    #[cfg(feature = "proposal_decorators")]
+    Accessor {
+        /// Whether the accessor is static.
+        is_static: bool,
+        /// The name of the accessor.
+        name: String,
+        /// Initial value of the accessor.
+        initializer: Option<Box<Statement>>
+    }
+}
+
+/// The kind of a class method.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MethodKind {
+    Constructor, Get, Set, Method
+}
+
+/// Parameter of a function or method (eg. `x`, `y = 42` or `...rest`).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FunctionParam {
+    /// The name of the parameter.
+    pub name: String,
+    /// Value used when the argument is undefined.
+    pub default_value: Option<Box<Statement>>,
+    /// Whether the parameter collects the remaining arguments (eg. `...rest`).
+    pub rest: bool,
+    /// Type of the parameter (eg. `x: number`).
+    #[cfg(feature = "typescript")]
+    pub type_ann: Option<TsType>,
+}
+
+impl FunctionParam {
+    /// Create a new plain parameter.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            default_value: None,
+            rest: false,
+            #[cfg(feature = "typescript")]
+            type_ann: None,
+        }
+    }
+
+    /// Create a new rest parameter collecting the remaining arguments.
+    pub fn rest(name: &str) -> Self {
+        Self {
+            rest: true,
+            ..Self::new(name)
+        }
+    }
+
+    /// Set the value used when the argument is undefined.
+    pub fn with_default(mut self, default_value: impl Into<Statement>) -> Self {
+        self.default_value = Some(Box::new(default_value.into()));
+        self
+    }
+
+    /// Set the type of the parameter.
+    #[cfg(feature = "typescript")]
+    pub fn with_type(mut self, type_ann: TsType) -> Self {
+        self.type_ann = Some(type_ann);
+        self
+    }
+
+    /// Create js code for the parameter, fails if the name is not a valid identifier or a rest parameter has a default value.
+    /// In declaration mode, parameters with default values are optional instead (eg. `y?: number`).
+    pub fn generate_indented(&self, indent: usize, options: &CodegenOptions) -> Result<String, CodegenError> {
+        validate_identifier(&self.name)?;
+        if self.rest && self.default_value.is_some() {
+            return Err(CodegenError::InvalidStatement {
+                context: format!("Rest parameter `{}` can't have a default value", self.name)
+            });
+        }
+
+        let mut code = if self.rest { format!("...{}", self.name) } else { self.name.clone() };
+        if options.is_declaration() && self.default_value.is_some() {
+            code.push('?');
+        }
+        #[cfg(feature = "typescript")]
+        if let Some(type_ann) = &self.type_ann {
+            code = format!("{}:{}{}", code, options.space(), type_ann.generate_with_options(options)?);
+        }
+        match &self.default_value {
+            Some(default_value) if !options.is_declaration() => {
+                // Parameter defaults can't use `await` or `yield`, not even in async functions or generators.
+                BlockContext::Normal.check(default_value)?;
+                let parens = default_value.precedence() < ASSIGNMENT_PRECEDENCE;
+                let default_value = default_value.generate_parenthesized(parens, indent, options)?;
+                Ok(options.join_tokens(&[&code, "=", &default_value]))
+            },
+            _ => Ok(code)
+        }
+    }
+}
+
+impl From<&str> for FunctionParam {
+    fn from(name: &str) -> Self {
+        Self::new(name)
+    }
+}
+
+impl From<String> for FunctionParam {
+    fn from(name: String) -> Self {
+        Self::new(&name)
+    }
+}
+
+impl ClassMember {
+    /// Create a new class constructor.
+    pub fn constructor(params: Vec<String>, body: Block) -> Self {
+        Self::new_method(MethodKind::Constructor, "constructor", params, body)
+    }
+
+    /// Create a new class method.
+    pub fn method(name: &str, params: Vec<String>, body: Block) -> Self {
+        Self::new_method(MethodKind::Method, name, params, body)
+    }
+
+    /// Create a new class getter.
+    pub fn getter(name: &str, body: Block) -> Self {
+        Self::new_method(MethodKind::Get, name, Vec::new(), body)
+    }
+
+    /// Create a new class setter.
+    pub fn setter(name: &str, param: &str, body: Block) -> Self {
+        Self::new_method(MethodKind::Set, name, vec![param.to_string()], body)
+    }
+
+    /// Create a new class field.
+    pub fn field(name: &str, value: Option<Statement>) -> Self {
+        Self::Field {
+            is_static: false,
+            is_private: false,
+            name: name.to_string(),
+            value: value.map(|value| value.into())
+        }
+    }
+
+    /// Create a new static class field.
+    pub fn static_field(name: &str, value: Option<Statement>) -> Self {
+        Self::Field {
+            is_static: true,
+            is_private: false,
+            name: name.to_string(),
+            value: value.map(|value| value.into())
+        }
+    }
+
+    /// Create a new private class field (name is given without the `#` prefix).
+    pub fn private_field(name: &str, value: Option<Statement>) -> Self {
+        Self::Field {
+            is_static: false,
+            is_private: true,
+            name: name.to_string(),
+            value: value.map(|value| value.into())
+        }
+    }
+
+    /// Create a new auto-accessor field.
+    #[cfg(feature = "proposal_decorators")]
+    pub fn accessor(name: &str, initializer: Option<Statement>) -> Self {
+        Self::Accessor {
+            is_static: false,
+            name: name.to_string(),
+            initializer: initializer.map(|initializer| initializer.into())
+        }
+    }
+
+    /// Create a new private class method (name is given without the `#` prefix).
+    pub fn private_method(name: &str, params: Vec<String>, body: Block) -> Self {
+        let mut method = Self::new_method(MethodKind::Method, name, params, body);
+        if let Self::Method { is_private, .. } = &mut method {
+            *is_private = true;
+        }
+        method
+    }
+
+    /// Replace the parameters of a method (eg. to add default values), other members are left unchanged.
+    pub fn with_params(mut self, new_params: Vec<FunctionParam>) -> Self {
+        if let Self::Method { params, .. } = &mut self {
+            *params = new_params;
+        }
+        self
+    }
+
+    fn new_method(kind: MethodKind, name: &str, params: Vec<String>, body: Block) -> Self {
+        Self::Method {
+            is_static: false,
+            is_async: false,
+            is_generator: false,
+            is_private: false,
+            kind,
+            name: name.to_string(),
+            params: params.into_iter().map(FunctionParam::from).collect(),
+            body: Box::new(body)
+        }
+    }
+
+    /// Get the statements directly contained in the member (field values and method body statements).
+    pub(crate) fn children(&self) -> Vec<&Statement> {
+        match self {
+            ClassMember::Method { params, body, .. } => params.iter()
+                .filter_map(|param| param.default_value.as_deref())
+                .chain(&body.statements)
+                .collect(),
+            ClassMember::Field { value, .. } => value.iter().map(|value| &**value).collect(),
+            #[cfg(feature = "proposal_decorators")]
+            ClassMember::Accessor { initializer, .. } => initializer.iter().map(|initializer| &**initializer).collect()
+        }
+    }
+
+    /// Get the statements directly contained in the member for modification.
+    pub(crate) fn children_mut(&mut self) -> Vec<&mut Statement> {
+        match self {
+            ClassMember::Method { params, body, .. } => params.iter_mut()
+                .filter_map(|param| param.default_value.as_deref_mut())
+                .chain(&mut body.statements)
+                .collect(),
+            ClassMember::Field { value, .. } => value.iter_mut().map(|value| &mut **value).collect(),
+            #[cfg(feature = "proposal_decorators")]
+            ClassMember::Accessor { initializer, .. } => initializer.iter_mut().map(|initializer| &mut **initializer).collect()
+        }
+    }
+
+    /// Get the values evaluated outside of the member's body (field values, accessor initializers
+    /// and parameter defaults), they can't use `await` or `yield`.
+    pub(crate) fn initializers(&self) -> Vec<&Statement> {
+        match self {
+            ClassMember::Method { params, .. } => params.iter().filter_map(|param| param.default_value.as_deref()).collect(),
+            ClassMember::Field { .. } => self.children(),
+            #[cfg(feature = "proposal_decorators")]
+            ClassMember::Accessor { .. } => self.children()
+        }
+    }
+
+    /// Check if the member is private (`#name`).
+    pub fn is_private(&self) -> bool {
+        match self {
+            ClassMember::Method { is_private, .. } | ClassMember::Field { is_private, .. } => *is_private,
+            #[cfg(feature = "proposal_decorators")]
+            ClassMember::Accessor { .. } => false
+        }
+    }
+
+    /// Create js code for the class member at the given indentation level.
+    /// Method bodies are indented one level deeper, the first line is never indented.
+    /// In declaration mode, methods are generated as signatures and initial values are omitted.
+    pub fn generate_indented(&self, indent: usize, options: &CodegenOptions) -> Result<String, CodegenError> {
+        let code = match self {
+            ClassMember::Method { is_static, is_async, is_generator, is_private, kind, name, params, body } => {
+                let mut head = String::new();
+                if *is_static {
+                    head.push_str("static ");
+                }
+                if *is_async && !options.is_declaration() {
+                    head.push_str("async ");
+                }
+                match kind {
+                    MethodKind::Get => head.push_str("get "),
+                    MethodKind::Set => head.push_str("set "),
+                    MethodKind::Constructor | MethodKind::Method => {}
+                }
+                if *is_generator && !options.is_declaration() {
+                    head.push('*');
+                }
+                match kind {
+                    MethodKind::Constructor => head.push_str("constructor"),
+                    _ => head.push_str(&member_name(name, *is_private)?)
+                }
+
+                let params = generate_params(params, indent, options)?;
+                if options.is_declaration() {
+                    format!("{}({}){}", head, params, options.member_terminator())
+                } else if body.statements.is_empty() {
+                    format!("{}({}){}{{}}", head, params, options.space())
+                } else {
+                    format!(
+                        "{}({}){}{{{}{}{}}}",
+                        head,
+                        params,
+                        options.space(),
+                        options.newline(),
+                        body.generate_in_context(BlockContext::function(*is_async, *is_generator), indent + 1, options)?,
+                        options.indent(indent)
+                    )
+                }
+            },
+            ClassMember::Field { is_static, is_private, name, value } => {
+                let is_static = if *is_static { "static " } else { "" };
+                let name = member_name(name, *is_private)?;
+                let terminator = options.member_terminator();
+                match value {
+                    Some(value) if !options.is_declaration() => {
+                        BlockContext::Normal.check(value)?;
+                        let value = value.generate_parenthesized(value.precedence() < ASSIGNMENT_PRECEDENCE, indent, options)?;
+                        format!("{}{}{}", is_static, options.join_tokens(&[&name, "=", &value]), terminator)
+                    },
+                    Some(value) => format!("{}{}:{}{}{}", is_static, name, options.space(), value.declared_type(), terminator),
+                    None => format!("{}{}{}", is_static, name, terminator)
+                }
+            },
+            #[cfg(feature = "proposal_decorators")]
+            ClassMember::Accessor { is_static, name, initializer } => {
+                let is_static = if *is_static { "static " } else { "" };
+                let terminator = options.member_terminator();
+                match initializer {
+                    Some(initializer) if !options.is_declaration() => {
+                        BlockContext::Normal.check(initializer)?;
+                        let parens = initializer.precedence() < ASSIGNMENT_PRECEDENCE;
+                        let initializer = initializer.generate_parenthesized(parens, indent, options)?;
+                        format!("{}accessor {}{}", is_static, options.join_tokens(&[name, "=", &initializer]), terminator)
+                    },
+                    _ => format!("{}accessor {}{}", is_static, name, terminator)
+                }
+            }
+        };
+        Ok(code)
+    }
+}
+
+/// Generate a parameter list without the parentheses, fails if a rest parameter is not the last one.
+fn generate_params(params: &[FunctionParam], indent: usize, options: &CodegenOptions) -> Result<String, CodegenError> {
+    if let Some(param) = params.iter().rev().skip(1).find(|param| param.rest) {
+        return Err(CodegenError::InvalidStatement {
+            context: format!("Rest parameter `{}` must be the last one", param.name)
+        });
+    }
+    Ok(params.iter()
+        .map(|param| param.generate_indented(indent, options))
+        .collect::<Result<Vec<_>, _>>()?
+        .join(&format!(",{}", options.space())))
+}
+
+/// Get the name of a class member as written in the class body.
+/// Private member names must not include the `#` prefix.
+fn member_name(name: &str, is_private: bool) -> Result<String, CodegenError> {
+    if !is_private {
+        return Ok(name.to_string());
+    }
+    if name.starts_with('#') {
+        return Err(CodegenError::InvalidIdentifier(name.to_string()));
+    }
+    Ok(format!("#{}", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codegen::{CodegenError, CodegenOptions, GenerationMode, SemicolonMode};
+    use crate::module::block::{Block, BlockContext, Statement, VarType};
+    use crate::module::class::{ClassMember, FunctionParam};
+
+    #[test]
+    fn test_class_decl() {
+        let mut constructor_body = Block::new(0);
+        constructor_body.raw("super()");
+        constructor_body.raw("this.x = x");
+        let mut getter_body = Block::new(0);
+        getter_body.raw("return this.x");
+
+        let mut block = Block::new(1);
+        block.class_decl("Foo", Some(Statement::Raw("Bar".to_string())), vec![
+            ClassMember::static_field("count", Some(0.into())),
+            ClassMember::field("x", None),
+            ClassMember::constructor(vec!["x".to_string()], constructor_body),
+            ClassMember::getter("value", getter_body),
+            ClassMember::method("noop", Vec::new(), Block::new(0)),
+        ]).unwrap();
+
+        assert_eq!(
+            block.generate().unwrap(),
+            concat!(
+                "    class Foo extends Bar {\n",
+                "        static count = 0;\n",
+                "        x;\n",
+                "        constructor(x) {\n",
+                "            super()\n",
+                "            this.x = x\n",
+                "        }\n",
+                "        get value() {\n",
+                "            return this.x\n",
+                "        }\n",
+                "        noop() {}\n",
+                "    }\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_private_class_members() {
+        let mut body = Block::new(0);
+        body.raw("return this.#secret");
+
+        let mut block = Block::new(0);
+        block.class_decl("Foo", None, vec![
+            ClassMember::field("visible", Some(1.into())),
+            ClassMember::private_field("secret", Some(42.into())),
+            ClassMember::private_method("reveal", Vec::new(), body),
+        ]).unwrap();
+
+        assert_eq!(
+            block.generate().unwrap(),
+            concat!(
+                "class Foo {\n",
+                "    visible = 1;\n",
+                "    #secret = 42;\n",
+                "    #reveal() {\n",
+                "        return this.#secret\n",
+                "    }\n",
+                "}\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_private_member_name_with_hash() {
+        let result = ClassMember::private_field("#secret", None).generate_indented(0, &CodegenOptions::default());
+        assert!(matches!(result, Err(CodegenError::InvalidIdentifier(name)) if name == "#secret"));
+    }
+
+    #[test]
+    #[cfg(feature = "proposal_decorators")]
+    fn test_accessor_class_member() {
+        let mut block = Block::new(0);
+        block.class_decl("Foo", None, vec![
+            ClassMember::accessor("count", Some(0.into())),
+            ClassMember::Accessor { is_static: true, name: "total".to_string(), initializer: None },
+        ]).unwrap();
+        assert_eq!(block.generate().unwrap(), "class Foo {\n    accessor count = 0;\n    static accessor total;\n}\n");
+    }
+
+    #[test]
+    #[cfg(feature = "proposal_decorators")]
+    fn test_accessor_initializer_expression() {
+        let sequence = Statement::Sequence(vec![1.into(), 2.into()]);
+        let accessor = ClassMember::accessor("count", Some(sequence));
+        assert_eq!(accessor.generate_indented(0, &CodegenOptions::default()).unwrap(), "accessor count = (1, 2);");
+
+        let accessor = ClassMember::accessor("count", Some(Statement::Debugger));
+        let result = accessor.generate_indented(0, &CodegenOptions::default());
+        assert!(matches!(result, Err(CodegenError::InvalidStatement { .. })));
+    }
+
+    #[test]
+    fn test_field_initializer_expression() {
+        let sequence = Statement::Sequence(vec![1.into(), 2.into()]);
+        let field = ClassMember::static_field("x", Some(sequence));
+        assert_eq!(field.generate_indented(0, &CodegenOptions::default()).unwrap(), "static x = (1, 2);");
+
+        let field = ClassMember::field("x", Some(Statement::var_decl(VarType::Let, "y", None)));
+        let result = field.generate_indented(0, &CodegenOptions::default());
+        assert!(matches!(result, Err(CodegenError::InvalidStatement { .. })));
+    }
+
+    #[test]
+    fn test_empty_class_decl() {
+        let mut block = Block::new(0);
+        block.class_decl("Foo", None, Vec::new()).unwrap();
+        assert_eq!(block.generate().unwrap(), "class Foo {}\n");
+    }
+
+    #[test]
+    fn test_class_fields_without_semicolons() {
+        let mut block = Block::new(0);
+        block.class_decl("Foo", None, vec![
+            ClassMember::field("x", Some(1.into())),
+            ClassMember::field("y", None),
+        ]).unwrap();
+        let options = CodegenOptions::new().with_semicolons(SemicolonMode::Never);
+        assert_eq!(block.generate_with_options(&options).unwrap(), "class Foo {\n    x = 1\n    y\n}\n");
+    }
+
+    #[test]
+    fn test_initializers_disallow_await_and_yield() {
+        let load = || Statement::Await(Box::new(Statement::Identifier("load".to_string()).call(Vec::new())));
+        let mut block = Block::new(0).with_context(BlockContext::AsyncGenerator);
+        let result = block.class_decl("A", None, vec![ClassMember::field("x", Some(load()))]);
+        assert!(matches!(result, Err(CodegenError::AwaitOutsideAsync)));
+
+        let yielded = Statement::Yield { value: None, delegate: false };
+        block.extend_stmts(vec![Statement::ClassDecl { name: "B".to_string(), superclass: None, body: vec![ClassMember::static_field("y", Some(yielded))] }]);
+        assert!(matches!(block.generate(), Err(CodegenError::YieldOutsideGenerator)));
+
+        let method = ClassMember::method("run", Vec::new(), Block::new(0))
+            .with_params(vec![FunctionParam::new("x").with_default(load())]);
+        assert!(matches!(method.generate_indented(0, &CodegenOptions::default()), Err(CodegenError::AwaitOutsideAsync)));
+    }
+
+    #[test]
+    fn test_method_params() {
+        let method = ClassMember::method("foo", Vec::new(), Block::new(0)).with_params(vec![
+            "x".into(),
+            FunctionParam::new("y").with_default(42),
+            FunctionParam::new("z").with_default("hi"),
+            FunctionParam::rest("rest"),
+        ]);
+        assert_eq!(method.generate_indented(0, &CodegenOptions::default()).unwrap(), "foo(x, y = 42, z = 'hi', ...rest) {}");
+        assert_eq!(
+            method.generate_indented(0, &CodegenOptions::new().with_minify(true)).unwrap(),
+            "foo(x,y=42,z='hi',...rest){}"
+        );
+        let options = CodegenOptions::new().with_mode(GenerationMode::TypeScriptDeclaration);
+        assert_eq!(method.generate_indented(0, &options).unwrap(), "foo(x, y?, z?, ...rest);");
+
+        let invalid = [
+            vec![FunctionParam::rest("rest"), FunctionParam::new("last")],
+            vec![FunctionParam::rest("rest").with_default(1)],
+            vec![FunctionParam::new("this")],
+        ];
+        for params in invalid {
+            let method = ClassMember::method("foo", Vec::new(), Block::new(0)).with_params(params);
+            assert!(method.generate_indented(0, &CodegenOptions::default()).is_err());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "typescript")]
+    fn test_typed_method_params() {
+        use crate::module::typescript::TsType;
+
+        let method = ClassMember::method("foo", Vec::new(), Block::new(0)).with_params(vec![
+            FunctionParam::new("x").with_type(TsType::ident("number")).with_default(1),
+            FunctionParam::rest("args").with_type(TsType::ident("string[]")),
+        ]);
+        assert_eq!(method.generate_indented(0, &CodegenOptions::default()).unwrap(), "foo(x: number = 1, ...args: string[]) {}");
+        let options = CodegenOptions::new().with_mode(GenerationMode::TypeScriptDeclaration);
+        assert_eq!(method.generate_indented(0, &options).unwrap(), "foo(x?: number, ...args: string[]);");
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::BTreeMap;
+
+use super::Module;
+use super::block::Statement;
+
+/// Collection of modules and the imports between them.
+/// Modules are referenced by import paths relative to each other (eg. `./foo.js` refers to module `foo`),
+/// imports of modules that are not in the graph are ignored.
+#[derive(Default)]
+pub struct ModuleGraph {
+    /// The modules of the graph keyed by their names.
+    pub modules: BTreeMap<String, Module>,
+}
+
+/// Error returned when modules can't be ordered because they import each other.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CycleError {
+    /// Names of the modules forming the cycle, the first module is repeated at the end.
+    pub cycle: Vec<String>,
+}
+
+impl std::fmt::Display for CycleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Circular dependency: {}", self.cycle.join(" -> "))
+    }
+}
+
+impl std::error::Error for CycleError {}
+
+impl ModuleGraph {
+    /// Create a new empty module graph.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a module to the graph, replacing any module with the same name.
+    pub fn add_module(&mut self, module: Module) -> &mut Self {
+        self.modules.insert(module.name.clone(), module);
+        self
+    }
+
+    /// Get the names of the modules in the graph imported (or re-exported) by the given module.
+    pub fn dependencies_of(&self, name: &str) -> Vec<&str> {
+        let Some(module) = self.modules.get(name) else {
+            return Vec::new();
+        };
+        let reexports = module.main_block.statements.iter().filter_map(|statement| match statement {
+            Statement::ExportList { from_path: Some(path), .. } | Statement::ExportAll(path) => Some(path),
+            _ => None
+        });
+
+        let mut dependencies = Vec::new();
+        for path in module.dependencies.iter().map(|dependency| &dependency.path).chain(reexports) {
+            if let Some((dependency, _)) = self.modules.get_key_value(module_name(path)) {
+                if !dependencies.contains(&dependency.as_str()) {
+                    dependencies.push(dependency.as_str());
+                }
+            }
+        }
+        dependencies
+    }
+
+    /// Find all circular dependencies, each cycle starts and ends with the same module.
+    pub fn check_cycles(&self) -> Vec<Vec<String>> {
+        let mut cycles = Vec::new();
+        let mut visited = Vec::new();
+        for name in self.modules.keys() {
+            self.visit(name, &mut Vec::new(), &mut visited, &mut |cycle| cycles.push(cycle), &mut |_| {});
+        }
+        cycles
+    }
+
+    /// Get the names of all modules ordered so that every module comes after the modules it imports.
+    pub fn topological_order(&self) -> Result<Vec<&str>, CycleError> {
+        let mut order = Vec::new();
+        let mut cycle = None;
+        let mut visited = Vec::new();
+        for name in self.modules.keys() {
+            self.visit(name, &mut Vec::new(), &mut visited, &mut |found| { cycle.get_or_insert(found); }, &mut |name| order.push(name));
+        }
+        match cycle {
+            Some(cycle) => Err(CycleError { cycle }),
+            None => Ok(order)
+        }
+    }
+
+    /// Depth-first search reporting cycles and modules in post-order.
+    fn visit<'a>(
+        &'a self,
+        name: &'a str,
+        stack: &mut Vec<&'a str>,
+        visited: &mut Vec<&'a str>,
+        on_cycle: &mut impl FnMut(Vec<String>),
+        on_finish: &mut impl FnMut(&'a str)
+    ) {
+        if let Some(start) = stack.iter().position(|entry| *entry == name) {
+            let mut cycle = stack[start..].iter().map(|entry| entry.to_string()).collect::<Vec<_>>();
+            cycle.push(name.to_string());
+            on_cycle(cycle);
+            return;
+        }
+        if visited.contains(&name) {
+            return;
+        }
+
+        stack.push(name);
+        for dependency in self.dependencies_of(name) {
+            self.visit(dependency, stack, visited, on_cycle, on_finish);
+        }
+        stack.pop();
+        visited.push(name);
+        on_finish(name);
+    }
+}
+
+/// Get the name of the module an import path refers to (eg. `./foo.js` is `foo`).
+fn module_name(path: &str) -> &str {
+    let path = path.strip_prefix("./").unwrap_or(path);
+    [".js", ".mjs", ".cjs", ".ts"].iter()
+        .find_map(|extension| path.strip_suffix(extension))
+        .unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::module::{Dependency, Module};
+    use crate::module::graph::ModuleGraph;
+
+    fn module(name: &str, imports: &[&str]) -> Module {
+        let mut module = Module::create(name);
+        for path in imports {
+            module.dep(Dependency::default("dep", path));
+        }
+        module
+    }
+
+    #[test]
+    fn test_module_graph_cycle() {
+        let mut graph = ModuleGraph::new();
+        graph.add_module(module("a", &["./b.js"]));
+        graph.add_module(module("b", &["./c.js", "react"]));
+        graph.add_module(module("c", &["./a.js"]));
+
+        assert_eq!(graph.check_cycles(), vec![vec!["a", "b", "c", "a"]]);
+        let error = graph.topological_order().unwrap_err();
+        assert_eq!(error.to_string(), "Circular dependency: a -> b -> c -> a");
+    }
+
+    #[test]
+    fn test_module_graph_topological_order() {
+        let mut graph = ModuleGraph::new();
+        graph.add_module(module("app", &["./utils.js", "./api.js"]));
+        graph.add_module(module("api", &["./utils.js"]));
+        graph.add_module(Module::create_barrel_all("utils", &["./strings.js"]));
+        graph.add_module(module("strings", &[]));
+
+        assert!(graph.check_cycles().is_empty());
+        assert_eq!(graph.topological_order().unwrap(), vec!["strings", "utils", "api", "app"]);
+    }
+}
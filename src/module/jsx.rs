@@ -0,0 +1,284 @@
+use super::block::Statement;
+use crate::codegen::{CodegenError, CodegenOptions};
+
+/// Name of a JSX element.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum JsxTag {
+    /// Intrinsic element or component name (eg. `div` or `Button`).
+    Ident(String),
+    /// Component accessed as a member (eg. `Foo.Bar`).
+    Member(Box<Statement>),
+}
+
+/// Attribute of a JSX element.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum JsxAttr {
+    /// Named attribute (eg. `className="foo"`).
+    Named {
+        /// The name of the attribute.
+        name: String,
+        /// The value of the attribute.
+        value: JsxAttrValue
+    },
+    /// Spread attributes (eg. `{...props}`).
+    Spread(Box<Statement>),
+}
+
+/// Value of a named JSX attribute.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum JsxAttrValue {
+    /// String value (eg. `"foo"`).
+    String(String),
+    /// Expression value (eg. `{count + 1}`).
+    Expression(Box<Statement>),
+    /// Attribute without a value, which is `true` (eg. `<input disabled />`).
+    Boolean,
+}
+
+/// Child of a JSX element or fragment.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum JsxChild {
+    /// Nested element or fragment.
+    Element(Box<Statement>),
+    /// Expression container (eg. `{name}`).
+    Expression(Box<Statement>),
+    /// Text content.
+    Text(String),
+}
+
+impl JsxTag {
+    /// Create a new tag from a name.
+    pub fn ident(name: &str) -> Self {
+        JsxTag::Ident(name.to_string())
+    }
+
+    fn generate_indented(&self, indent: usize, options: &CodegenOptions) -> Result<String, CodegenError> {
+        match self {
+            JsxTag::Ident(name) => Ok(name.clone()),
+            JsxTag::Member(member) => member.generate_indented(indent, options)
+        }
+    }
+}
+
+impl JsxAttr {
+    /// Create a new attribute with a string value.
+    pub fn string(name: &str, value: &str) -> Self {
+        JsxAttr::Named { name: name.to_string(), value: JsxAttrValue::String(value.to_string()) }
+    }
+
+    /// Create a new attribute with an expression value.
+    pub fn expression(name: &str, value: impl Into<Statement>) -> Self {
+        JsxAttr::Named { name: name.to_string(), value: JsxAttrValue::Expression(Box::new(value.into())) }
+    }
+
+    /// Create a new attribute without a value.
+    pub fn boolean(name: &str) -> Self {
+        JsxAttr::Named { name: name.to_string(), value: JsxAttrValue::Boolean }
+    }
+
+    /// Get the statements contained in the attribute.
+    pub(crate) fn children(&self) -> Vec<&Statement> {
+        match self {
+            JsxAttr::Named { value: JsxAttrValue::Expression(value), .. } | JsxAttr::Spread(value) => vec![value],
+            JsxAttr::Named { .. } => Vec::new()
+        }
+    }
+
+    /// Get the statements contained in the attribute for modification.
+    pub(crate) fn children_mut(&mut self) -> Vec<&mut Statement> {
+        match self {
+            JsxAttr::Named { value: JsxAttrValue::Expression(value), .. } | JsxAttr::Spread(value) => vec![value],
+            JsxAttr::Named { .. } => Vec::new()
+        }
+    }
+
+    fn generate_indented(&self, indent: usize, options: &CodegenOptions) -> Result<String, CodegenError> {
+        let code = match self {
+            JsxAttr::Named { name, value: JsxAttrValue::Boolean } => name.clone(),
+            // JSX strings can't contain escapes, so strings with double quotes are written as expressions.
+            JsxAttr::Named { name, value: JsxAttrValue::String(value) } if value.contains('"') => {
+                format!("{}={{{}}}", name, options.quote_style.quote(value))
+            },
+            JsxAttr::Named { name, value: JsxAttrValue::String(value) } => format!("{}=\"{}\"", name, value),
+            JsxAttr::Named { name, value: JsxAttrValue::Expression(value) } => {
+                value.check_expression()?;
+                format!("{}={{{}}}", name, value.generate_indented(indent, options)?)
+            },
+            JsxAttr::Spread(value) => format!("{{...{}}}", value.generate_indented(indent, options)?)
+        };
+        Ok(code)
+    }
+}
+
+impl JsxChild {
+    /// Create a new text child.
+    pub fn text(text: &str) -> Self {
+        JsxChild::Text(text.to_string())
+    }
+
+    /// Get the statement contained in the child.
+    pub(crate) fn children(&self) -> Vec<&Statement> {
+        match self {
+            JsxChild::Element(child) | JsxChild::Expression(child) => vec![child],
+            JsxChild::Text(_) => Vec::new()
+        }
+    }
+
+    /// Get the statement contained in the child for modification.
+    pub(crate) fn children_mut(&mut self) -> Vec<&mut Statement> {
+        match self {
+            JsxChild::Element(child) | JsxChild::Expression(child) => vec![child],
+            JsxChild::Text(_) => Vec::new()
+        }
+    }
+
+    fn generate_indented(&self, indent: usize, options: &CodegenOptions) -> Result<String, CodegenError> {
+        let code = match self {
+            JsxChild::Element(element) => {
+                if !matches!(**element, Statement::JsxElement { .. } | Statement::JsxFragment { .. }) {
+                    return Err(CodegenError::InvalidStatement {
+                        context: format!("Expected JSX element, got {:?}", element)
+                    });
+                }
+                element.generate_indented(indent, options)?
+            },
+            JsxChild::Expression(expression) => {
+                expression.check_expression()?;
+                format!("{{{}}}", expression.generate_indented(indent, options)?)
+            },
+            // Text with characters that have a meaning in JSX is written as a string expression.
+            JsxChild::Text(text) if text.contains(['{', '}', '<', '>']) => {
+                format!("{{{}}}", options.quote_style.quote_literal(text))
+            },
+            JsxChild::Text(text) => text.clone()
+        };
+        Ok(code)
+    }
+}
+
+/// Generate a JSX element (eg. `<div className="foo">{bar}</div>`).
+pub(crate) fn generate_element(
+    tag: &JsxTag,
+    attributes: &[JsxAttr],
+    children: &[JsxChild],
+    self_closing: bool,
+    indent: usize,
+    options: &CodegenOptions
+) -> Result<String, CodegenError> {
+    let tag = tag.generate_indented(indent, options)?;
+    // Attributes are always separated with spaces, even when minifying.
+    let mut head = format!("<{}", tag);
+    for attribute in attributes {
+        head.push(' ');
+        head.push_str(&attribute.generate_indented(indent, options)?);
+    }
+
+    if self_closing {
+        if !children.is_empty() {
+            return Err(CodegenError::InvalidStatement {
+                context: format!("Self-closing JSX element `{}` can't have children", tag)
+            });
+        }
+        return Ok(format!("{}{}/>", head, options.space()));
+    }
+
+    Ok(format!("{}>{}</{}>", head, generate_children(children, indent, options)?, tag))
+}
+
+/// Generate a JSX fragment (eg. `<>foo</>`).
+pub(crate) fn generate_fragment(children: &[JsxChild], indent: usize, options: &CodegenOptions) -> Result<String, CodegenError> {
+    Ok(format!("<>{}</>", generate_children(children, indent, options)?))
+}
+
+fn generate_children(children: &[JsxChild], indent: usize, options: &CodegenOptions) -> Result<String, CodegenError> {
+    children.iter()
+        .map(|child| child.generate_indented(indent, options))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codegen::CodegenOptions;
+    use crate::module::block::{Block, Statement};
+    use crate::module::jsx::{JsxAttr, JsxChild, JsxTag};
+
+    #[test]
+    fn test_jsx_element() {
+        let button = Statement::JsxElement {
+            tag: JsxTag::Member(Box::new(Statement::Raw("UI.Button".to_string()))),
+            attributes: vec![
+                JsxAttr::expression("onClick", Statement::Raw("increment".to_string())),
+                JsxAttr::boolean("disabled"),
+            ],
+            children: vec![JsxChild::text("Add")],
+            self_closing: false
+        };
+        let element = Statement::JsxElement {
+            tag: JsxTag::ident("div"),
+            attributes: vec![
+                JsxAttr::string("className", "counter"),
+                JsxAttr::string("title", "say \"hi\""),
+                JsxAttr::Spread(Box::new(Statement::Raw("props".to_string()))),
+            ],
+            children: vec![
+                JsxChild::text("Count: "),
+                JsxChild::Expression(Box::new(Statement::Raw("count".to_string()))),
+                JsxChild::text(" {raw}"),
+                JsxChild::Element(Box::new(button)),
+                JsxChild::Element(Box::new(Statement::JsxElement {
+                    tag: JsxTag::ident("br"),
+                    attributes: Vec::new(),
+                    children: Vec::new(),
+                    self_closing: true
+                })),
+            ],
+            self_closing: false
+        };
+
+        assert_eq!(
+            element.generate().unwrap(),
+            concat!(
+                "<div className=\"counter\" title={'say \"hi\"'} {...props}>",
+                "Count: {count}{' {raw}'}<UI.Button onClick={increment} disabled>Add</UI.Button><br /></div>"
+            )
+        );
+        assert_eq!(
+            element.generate_with_options(&CodegenOptions::new().with_minify(true)).unwrap(),
+            concat!(
+                "<div className=\"counter\" title={'say \"hi\"'} {...props}>",
+                "Count: {count}{' {raw}'}<UI.Button onClick={increment} disabled>Add</UI.Button><br/></div>"
+            )
+        );
+    }
+
+    #[test]
+    fn test_jsx_fragment() {
+        let mut block = Block::new(0);
+        block.stmt(Statement::JsxFragment {
+            children: vec![
+                JsxChild::Element(Box::new(Statement::JsxElement {
+                    tag: JsxTag::ident("App"),
+                    attributes: Vec::new(),
+                    children: Vec::new(),
+                    self_closing: true
+                })),
+                JsxChild::text("done"),
+            ]
+        }).unwrap();
+        assert_eq!(block.generate().unwrap(), "<><App />done</>;\n");
+
+        let invalid = Statement::JsxElement {
+            tag: JsxTag::ident("br"),
+            attributes: Vec::new(),
+            children: vec![JsxChild::text("text")],
+            self_closing: true
+        };
+        assert!(invalid.generate().is_err());
+        let invalid = Statement::JsxFragment { children: vec![JsxChild::Element(Box::new(Statement::Null))] };
+        assert!(invalid.generate().is_err());
+    }
+}
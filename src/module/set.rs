@@ -0,0 +1,97 @@
+use std::path::{Path, PathBuf};
+
+use super::Module;
+use crate::codegen::CodegenError;
+
+/// Collection of modules written to the same directory.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModuleSet {
+    /// The modules of the set.
+    pub modules: Vec<Module>,
+}
+
+impl ModuleSet {
+    /// Create a new empty module set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new module set from the given modules.
+    pub fn from_modules(modules: Vec<Module>) -> Self {
+        Self { modules }
+    }
+
+    /// Add a module to the set.
+    pub fn add(&mut self, module: Module) {
+        self.modules.push(module);
+    }
+
+    /// Create an index module re-exporting everything from each module of the set (eg. `export * from './foo.js'`).
+    /// The index uses the options of the first module, default exports are not re-exported.
+    pub fn index(&self) -> Module {
+        let paths = self.modules.iter()
+            .map(|module| format!("./{}.js", module.name))
+            .collect::<Vec<_>>();
+        let mut index = Module::create_barrel_all("index", &paths.iter().map(String::as_str).collect::<Vec<_>>());
+        if let Some(module) = self.modules.first() {
+            index.set_options(module.options.clone());
+        }
+        index
+    }
+
+    /// Write each module to the directory (as `{name}.js` or `{name}.ts` depending on its options),
+    /// followed by an index module re-exporting all of them. The directory is created if it doesn't exist.
+    /// No index is written if one of the modules is already called `index`.
+    /// Returns the paths of all files that were written.
+    pub fn write_to_dir(&self, dir: &Path) -> Result<Vec<PathBuf>, CodegenError> {
+        std::fs::create_dir_all(dir)?;
+
+        let mut paths = Vec::new();
+        for module in &self.modules {
+            paths.push(module.generate(&dir.join(module.file_name(&module.options)))?);
+        }
+        if !self.modules.is_empty() && !self.modules.iter().any(|module| module.name == "index") {
+            let index = self.index();
+            paths.push(index.generate(&dir.join(index.file_name(&index.options)))?);
+        }
+        Ok(paths)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codegen::{CodegenOptions, GenerationMode};
+    use crate::module::block::{Statement, VarType};
+    use crate::module::set::ModuleSet;
+    use crate::module::Module;
+
+    #[test]
+    fn test_write_to_dir() {
+        let mut foo = Module::create("foo");
+        foo.export(Statement::var_decl(VarType::Const, "foo", Some(1.into())));
+        let mut bar = Module::create("bar");
+        bar.export(Statement::var_decl(VarType::Const, "bar", Some(2.into())));
+
+        let mut set = ModuleSet::from_modules(vec![foo]);
+        set.add(bar);
+
+        let dir = std::env::temp_dir().join("nauvi_test_write_to_dir");
+        let paths = set.write_to_dir(&dir).unwrap();
+        assert_eq!(paths, vec![dir.join("foo.js"), dir.join("bar.js"), dir.join("index.js")]);
+        assert_eq!(std::fs::read_to_string(dir.join("foo.js")).unwrap(), "export const foo = 1;\n");
+        assert_eq!(std::fs::read_to_string(dir.join("bar.js")).unwrap(), "export const bar = 2;\n");
+        assert_eq!(
+            std::fs::read_to_string(dir.join("index.js")).unwrap(),
+            "export * from './foo.js';\nexport * from './bar.js';\n"
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        for module in &mut set.modules {
+            module.set_options(CodegenOptions::new().with_mode(GenerationMode::TypeScript));
+        }
+        let dir = std::env::temp_dir().join("nauvi_test_write_to_dir_ts");
+        let paths = set.write_to_dir(&dir).unwrap();
+        assert_eq!(paths, vec![dir.join("foo.ts"), dir.join("bar.ts"), dir.join("index.ts")]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,122 @@
+use super::Module;
+use super::block::{Block, Statement};
+
+/// Pass that rewrites statements, blocks and modules.
+/// All methods default to rebuilding their input unchanged, so a transformer only overrides what it needs.
+pub trait Transformer {
+    /// Transform a statement, by default only its children are transformed (see `transform_children`).
+    fn transform_statement(&mut self, statement: Statement) -> Statement {
+        transform_children(self, statement)
+    }
+
+    /// Transform a block, by default each of its statements is transformed.
+    fn transform_block(&mut self, mut block: Block) -> Block {
+        block.statements = block.statements.into_iter()
+            .map(|statement| self.transform_statement(statement))
+            .collect();
+        block
+    }
+
+    /// Transform a module, by default its main block is transformed.
+    fn transform_module(&mut self, mut module: Module) -> Module {
+        module.main_block = self.transform_block(module.main_block);
+        module
+    }
+}
+
+/// Transform the direct children of a statement, keeping the statement itself.
+pub fn transform_children<T: Transformer + ?Sized>(transformer: &mut T, mut statement: Statement) -> Statement {
+    for child in statement.children_mut() {
+        let taken = std::mem::replace(child, Statement::Null);
+        *child = transformer.transform_statement(taken);
+    }
+    statement
+}
+
+/// Transformer that returns its inputs unchanged.
+pub struct IdentityTransformer;
+
+impl Transformer for IdentityTransformer {}
+
+/// Transformer that evaluates binary expressions of two integer literals (eg. `2 + 3` becomes `5`).
+/// Expressions are folded bottom-up, results that are not integers are left as they are. Only integers that
+/// JavaScript numbers represent exactly (up to `Number.MAX_SAFE_INTEGER` in magnitude) are folded.
+pub struct InlineConstantFolder;
+
+impl Transformer for InlineConstantFolder {
+    fn transform_statement(&mut self, statement: Statement) -> Statement {
+        let statement = transform_children(self, statement);
+        let Statement::Binary { left, operator, right } = &statement else {
+            return statement;
+        };
+        let (Statement::Literal { value: left }, Statement::Literal { value: right }) = (&**left, &**right) else {
+            return statement;
+        };
+        let (Ok(left), Ok(right)) = (left.parse::<i64>(), right.parse::<i64>()) else {
+            return statement;
+        };
+        if !is_safe_integer(left) || !is_safe_integer(right) {
+            return statement;
+        }
+
+        let folded = match operator.as_str() {
+            "+" => left.checked_add(right),
+            "-" => left.checked_sub(right),
+            "*" => left.checked_mul(right),
+            "/" if right != 0 && left.checked_rem(right) == Some(0) => left.checked_div(right),
+            "%" if right != 0 => left.checked_rem(right),
+            _ => None
+        };
+        match folded {
+            Some(value) if is_safe_integer(value) => value.into(),
+            _ => statement
+        }
+    }
+}
+
+/// Largest integer JavaScript numbers represent exactly (`Number.MAX_SAFE_INTEGER`).
+const MAX_SAFE_INTEGER: i64 = (1 << 53) - 1;
+
+/// Check if JavaScript evaluates arithmetic on the integer exactly.
+fn is_safe_integer(value: i64) -> bool {
+    (-MAX_SAFE_INTEGER..=MAX_SAFE_INTEGER).contains(&value)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{binary, ident};
+    use crate::module::Module;
+    use crate::module::block::Statement;
+    use crate::module::transform::{IdentityTransformer, InlineConstantFolder, Transformer};
+
+    #[test]
+    fn test_inline_constant_folder() {
+        let mut folder = InlineConstantFolder;
+        assert_eq!(folder.transform_statement(binary!(+ 2, 3)), Statement::Literal { value: "5".to_string() });
+        assert_eq!(folder.transform_statement(binary!(* binary!(+ 1, 2), 3)), Statement::Literal { value: "9".to_string() });
+        assert_eq!(folder.transform_statement(binary!(/ 1, 2)), binary!(/ 1, 2));
+
+        let mut module = Module::create("foo");
+        module.var_decl(crate::module::block::VarType::Const, "x", Some(binary!(- 10, 4))).unwrap();
+        let module = folder.transform_module(module);
+        assert_eq!(module.to_js_string().unwrap(), "const x = 6;\n");
+    }
+
+    #[test]
+    fn test_inline_constant_folder_limits() {
+        let mut folder = InlineConstantFolder;
+        assert_eq!(folder.transform_statement(binary!(/ i64::MIN, -1)), binary!(/ i64::MIN, -1));
+        assert_eq!(folder.transform_statement(binary!(+ 9007199254740992i64, 1)), binary!(+ 9007199254740992i64, 1));
+        assert_eq!(folder.transform_statement(binary!(+ 9007199254740990i64, 2)), binary!(+ 9007199254740990i64, 2));
+        assert_eq!(
+            folder.transform_statement(binary!(+ 9007199254740990i64, 1)),
+            Statement::Literal { value: "9007199254740991".to_string() }
+        );
+    }
+
+    #[test]
+    fn test_identity_transformer() {
+        let statement = binary!(+ 2, ident!(x));
+        assert_eq!(IdentityTransformer.transform_statement(statement.clone()), statement);
+    }
+}
@@ -0,0 +1,439 @@
+use super::block::Statement;
+use crate::codegen::{CodegenError, CodegenOptions};
+
+/// TypeScript type.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TsType {
+    /// Named type (eg. `number` or `Foo`).
+    Ident(String),
+    /// Union of types (eg. `string | number`).
+    Union(Vec<TsType>),
+    /// Intersection of types (eg. `Foo & Bar`).
+    Intersection(Vec<TsType>),
+    /// Array of a type (eg. `string[]`).
+    Array(Box<TsType>),
+    /// Type that can also be `null` (eg. `string | null`).
+    Nullable(Box<TsType>),
+    /// Literal type (eg. `'foo'` or `42`).
+    Literal(Box<Statement>),
+    /// Object type (eg. `{ ok: T; err?: E }`).
+    Object(Vec<TsInterfaceMember>),
+    /// Union of the keys of a type (eg. `keyof Foo`).
+    KeyOf(Box<TsType>),
+    /// Type of a value (eg. `typeof foo`), the value must be an identifier or a member access.
+    TypeOf(Box<Statement>),
+    /// Generic type with type arguments (eg. `ReturnType<F>` or `Record<K, V>`).
+    Utility {
+        /// The name of the type.
+        name: String,
+        /// The type arguments, there must be at least one.
+        args: Vec<TsType>
+    },
+    /// Object returned by an async generator function (eg. `AsyncGenerator<number, void, unknown>`).
+    AsyncGenerator {
+        /// The type of the yielded values.
+        yield_type: Box<TsType>,
+        /// The type of the returned value.
+        return_type: Box<TsType>,
+        /// The type of the values passed to `next()`.
+        next_type: Box<TsType>
+    },
+    /// Object that can be iterated with `for await` (eg. `AsyncIterable<string>`).
+    AsyncIterable(Box<TsType>),
+}
+
+impl TsType {
+    /// Create a new named type.
+    pub fn ident(name: &str) -> Self {
+        TsType::Ident(name.to_string())
+    }
+
+    /// Create a new generic type with the type arguments (eg. `ReturnType<F>`).
+    pub fn utility(name: &str, args: Vec<TsType>) -> Self {
+        TsType::Utility { name: name.to_string(), args }
+    }
+
+    /// Create a new async generator type (eg. `AsyncGenerator<number, void, unknown>`).
+    pub fn async_generator(yield_type: TsType, return_type: TsType, next_type: TsType) -> Self {
+        TsType::AsyncGenerator {
+            yield_type: Box::new(yield_type),
+            return_type: Box::new(return_type),
+            next_type: Box::new(next_type)
+        }
+    }
+
+    /// Create TypeScript code for the type.
+    pub fn generate(&self) -> Result<String, CodegenError> {
+        self.generate_with_options(&CodegenOptions::default())
+    }
+
+    /// Create TypeScript code for the type using the given options.
+    pub fn generate_with_options(&self, options: &CodegenOptions) -> Result<String, CodegenError> {
+        let separator = |operator: &str| format!("{}{}{}", options.space(), operator, options.space());
+        let code = match self {
+            TsType::Ident(name) => name.clone(),
+            TsType::Union(types) => self.generate_members(types, options)?.join(&separator("|")),
+            TsType::Intersection(types) => self.generate_members(types, options)?.join(&separator("&")),
+            TsType::Array(element) => format!("{}[]", element.generate_member(self, options)?),
+            TsType::Nullable(inner) => format!("{}{}null", inner.generate_member(self, options)?, separator("|")),
+            TsType::Literal(value) => value.generate_with_options(options)?,
+            TsType::Object(members) if members.is_empty() => "{}".to_string(),
+            TsType::Object(members) => {
+                let members = members.iter()
+                    .map(|member| member.generate_signature(options))
+                    .collect::<Result<Vec<_>, _>>()?
+                    .join(&format!(";{}", options.space()));
+                options.join_tokens(&["{", &members, "}"])
+            },
+            TsType::KeyOf(inner) => format!("keyof {}", inner.generate_member(self, options)?),
+            TsType::TypeOf(value) => {
+                if !is_entity_name(value) {
+                    return Err(CodegenError::InvalidStatement {
+                        context: format!("Expected identifier or member access in `typeof` type, got {:?}", value)
+                    });
+                }
+                format!("typeof {}", value.generate_with_options(options)?)
+            },
+            TsType::Utility { name, args } => generate_generic(name, args.iter(), options)?,
+            TsType::AsyncGenerator { yield_type, return_type, next_type } => {
+                generate_generic("AsyncGenerator", [&**yield_type, return_type, next_type].into_iter(), options)?
+            },
+            TsType::AsyncIterable(element) => generate_generic("AsyncIterable", std::iter::once(&**element), options)?
+        };
+        Ok(code)
+    }
+
+    fn generate_members(&self, types: &[TsType], options: &CodegenOptions) -> Result<Vec<String>, CodegenError> {
+        types.iter().map(|member| member.generate_member(self, options)).collect()
+    }
+
+    /// Generate the type as a part of another type, wrapping it in parentheses if it binds looser than its parent
+    /// (eg. a union inside of an array is `(string | number)[]`).
+    fn generate_member(&self, parent: &TsType, options: &CodegenOptions) -> Result<String, CodegenError> {
+        let code = self.generate_with_options(options)?;
+        if self.precedence() < parent.precedence() {
+            Ok(format!("({})", code))
+        } else {
+            Ok(code)
+        }
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            TsType::Union(_) | TsType::Nullable(_) => 1,
+            TsType::Intersection(_) => 2,
+            TsType::KeyOf(_) => 3,
+            TsType::Array(_) => 4,
+            TsType::Ident(_) | TsType::Literal(_) | TsType::Object(_) | TsType::TypeOf(_) | TsType::Utility { .. }
+                | TsType::AsyncGenerator { .. } | TsType::AsyncIterable(_) => 5
+        }
+    }
+}
+
+/// Generate a generic type with the type arguments (eg. `Record<K, V>`), fails if there are no type arguments.
+fn generate_generic<'a>(name: &str, args: impl Iterator<Item = &'a TsType>, options: &CodegenOptions) -> Result<String, CodegenError> {
+    let args = args
+        .map(|arg| arg.generate_with_options(options))
+        .collect::<Result<Vec<_>, _>>()?;
+    if args.is_empty() {
+        return Err(CodegenError::InvalidStatement {
+            context: format!("Generic type `{}` must have type arguments", name)
+        });
+    }
+    Ok(format!("{}<{}>", name, args.join(&format!(",{}", options.space()))))
+}
+
+/// Check if the statement can be used in a `typeof` type (an identifier, optionally followed by property accesses).
+fn is_entity_name(statement: &Statement) -> bool {
+    match statement {
+        Statement::Identifier(_) | Statement::Raw(_) => true,
+        Statement::MemberAccess { object, .. } => is_entity_name(object),
+        _ => false
+    }
+}
+
+/// Parameter of a TypeScript function or method signature (eg. `name?: string`).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TsParam {
+    /// The name of the parameter.
+    pub name: String,
+    /// Whether the parameter is optional.
+    pub optional: bool,
+    /// The type of the parameter.
+    pub type_ann: TsType,
+}
+
+impl TsParam {
+    /// Create a new required parameter.
+    pub fn new(name: &str, type_ann: TsType) -> Self {
+        Self { name: name.to_string(), optional: false, type_ann }
+    }
+
+    /// Create a new optional parameter.
+    pub fn optional(name: &str, type_ann: TsType) -> Self {
+        Self { name: name.to_string(), optional: true, type_ann }
+    }
+
+    /// Create TypeScript code for the parameter using the given options.
+    pub fn generate_with_options(&self, options: &CodegenOptions) -> Result<String, CodegenError> {
+        let optional = if self.optional { "?" } else { "" };
+        Ok(format!("{}{}:{}{}", self.name, optional, options.space(), self.type_ann.generate_with_options(options)?))
+    }
+}
+
+/// Member of a TypeScript interface.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TsInterfaceMember {
+    /// Property signature (eg. `readonly name?: string`).
+    Property {
+        /// The name of the property.
+        name: String,
+        /// Whether the property is optional.
+        optional: bool,
+        /// Whether the property is readonly.
+        readonly: bool,
+        /// The type of the property.
+        type_ann: TsType
+    },
+    /// Method signature (eg. `greet(name: string): void`).
+    Method {
+        /// The name of the method.
+        name: String,
+        /// The parameters of the method.
+        params: Vec<TsParam>,
+        /// The return type of the method.
+        return_type: TsType
+    },
+    /// Index signature (eg. `[key: string]: number`).
+    IndexSignature {
+        /// The name of the key.
+        key_name: String,
+        /// The type of the key.
+        key_type: TsType,
+        /// The type of the values.
+        value_type: TsType
+    },
+}
+
+impl TsInterfaceMember {
+    /// Create a new required property.
+    pub fn property(name: &str, type_ann: TsType) -> Self {
+        TsInterfaceMember::Property { name: name.to_string(), optional: false, readonly: false, type_ann }
+    }
+
+    /// Create a new optional property.
+    pub fn optional_property(name: &str, type_ann: TsType) -> Self {
+        TsInterfaceMember::Property { name: name.to_string(), optional: true, readonly: false, type_ann }
+    }
+
+    /// Create TypeScript code for the member using the given options.
+    pub fn generate_with_options(&self, options: &CodegenOptions) -> Result<String, CodegenError> {
+        Ok(format!("{}{}", self.generate_signature(options)?, options.member_terminator()))
+    }
+
+    /// Create TypeScript code for the member without the terminator.
+    fn generate_signature(&self, options: &CodegenOptions) -> Result<String, CodegenError> {
+        let code = match self {
+            TsInterfaceMember::Property { name, optional, readonly, type_ann } => {
+                let readonly = if *readonly { "readonly " } else { "" };
+                let optional = if *optional { "?" } else { "" };
+                format!("{}{}{}:{}{}", readonly, name, optional, options.space(), type_ann.generate_with_options(options)?)
+            },
+            TsInterfaceMember::Method { name, params, return_type } => {
+                let params = params.iter()
+                    .map(|param| param.generate_with_options(options))
+                    .collect::<Result<Vec<_>, _>>()?
+                    .join(&format!(",{}", options.space()));
+                format!("{}({}):{}{}", name, params, options.space(), return_type.generate_with_options(options)?)
+            },
+            TsInterfaceMember::IndexSignature { key_name, key_type, value_type } => {
+                format!(
+                    "[{}:{}{}]:{}{}",
+                    key_name,
+                    options.space(),
+                    key_type.generate_with_options(options)?,
+                    options.space(),
+                    value_type.generate_with_options(options)?
+                )
+            }
+        };
+        Ok(code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codegen::{CodegenOptions, QuoteStyle};
+    use crate::module::block::{Block, Statement, VarType};
+    use crate::module::typescript::{TsInterfaceMember, TsParam, TsType};
+
+    #[test]
+    fn test_ts_types() {
+        let union = TsType::Union(vec![TsType::ident("string"), TsType::ident("number")]);
+        assert_eq!(union.generate().unwrap(), "string | number");
+        assert_eq!(TsType::Array(Box::new(union.clone())).generate().unwrap(), "(string | number)[]");
+        assert_eq!(
+            TsType::Intersection(vec![TsType::ident("Foo"), union]).generate().unwrap(),
+            "Foo & (string | number)"
+        );
+        assert_eq!(TsType::Nullable(Box::new(TsType::Array(Box::new(TsType::ident("Foo"))))).generate().unwrap(), "Foo[] | null");
+        assert_eq!(TsType::Literal(Box::new("foo".into())).generate().unwrap(), "'foo'");
+    }
+
+    #[test]
+    fn test_ts_utility_types() {
+        let config = TsType::TypeOf(Box::new(Statement::Identifier("config".to_string()).member("defaults")));
+        assert_eq!(config.generate().unwrap(), "typeof config.defaults");
+        let keys = TsType::KeyOf(Box::new(config));
+        assert_eq!(keys.generate().unwrap(), "keyof typeof config.defaults");
+        assert_eq!(TsType::Array(Box::new(keys.clone())).generate().unwrap(), "(keyof typeof config.defaults)[]");
+        assert_eq!(
+            TsType::KeyOf(Box::new(TsType::Union(vec![TsType::ident("A"), TsType::ident("B")]))).generate().unwrap(),
+            "keyof (A | B)"
+        );
+
+        let record = TsType::utility("Record", vec![keys, TsType::utility("ReturnType", vec![TsType::ident("F")])]);
+        assert_eq!(record.generate().unwrap(), "Record<keyof typeof config.defaults, ReturnType<F>>");
+        assert!(TsType::utility("Partial", Vec::new()).generate().is_err());
+        assert!(TsType::TypeOf(Box::new(1.into())).generate().is_err());
+    }
+
+    #[test]
+    fn test_ts_async_iterator_types() {
+        let generator = TsType::async_generator(TsType::ident("number"), TsType::ident("void"), TsType::ident("unknown"));
+        assert_eq!(generator.generate().unwrap(), "AsyncGenerator<number, void, unknown>");
+        assert_eq!(
+            TsType::Nullable(Box::new(generator)).generate_with_options(&CodegenOptions::new().with_minify(true)).unwrap(),
+            "AsyncGenerator<number,void,unknown>|null"
+        );
+
+        let lines = TsType::AsyncIterable(Box::new(TsType::Union(vec![TsType::ident("string"), TsType::ident("Buffer")])));
+        assert_eq!(lines.generate().unwrap(), "AsyncIterable<string | Buffer>");
+        assert_eq!(TsType::Array(Box::new(lines)).generate().unwrap(), "AsyncIterable<string | Buffer>[]");
+    }
+
+    #[test]
+    fn test_typed_var_decl() {
+        let mut block = Block::new(0);
+        block.typed_var_decl(VarType::Let, "x", TsType::ident("number"), Some(5.into())).unwrap();
+        block.typed_var_decl(VarType::Let, "y", TsType::Nullable(Box::new(TsType::ident("string"))), None).unwrap();
+        block.stmt(Statement::var_decl(VarType::Const, "z", Some(1.into()))).unwrap();
+        assert_eq!(block.generate().unwrap(), "let x: number = 5;\nlet y: string | null;\nconst z = 1;\n");
+    }
+
+    #[test]
+    fn test_ts_interface() {
+        let mut block = Block::new(0);
+        block.ts_interface("User", vec!["Entity".to_string(), "Named".to_string()], vec![
+            TsInterfaceMember::Property { name: "id".to_string(), optional: false, readonly: true, type_ann: TsType::ident("number") },
+            TsInterfaceMember::optional_property("email", TsType::ident("string")),
+            TsInterfaceMember::Method {
+                name: "greet".to_string(),
+                params: vec![TsParam::new("name", TsType::ident("string")), TsParam::optional("times", TsType::ident("number"))],
+                return_type: TsType::ident("void")
+            },
+            TsInterfaceMember::IndexSignature {
+                key_name: "key".to_string(),
+                key_type: TsType::ident("string"),
+                value_type: TsType::ident("unknown")
+            },
+        ]).unwrap();
+        block.ts_interface("Empty", Vec::new(), Vec::new()).unwrap();
+
+        assert_eq!(
+            block.generate().unwrap(),
+            concat!(
+                "interface User extends Entity, Named {\n",
+                "    readonly id: number;\n",
+                "    email?: string;\n",
+                "    greet(name: string, times?: number): void;\n",
+                "    [key: string]: unknown;\n",
+                "}\n",
+                "interface Empty {}\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_ts_type_alias() {
+        let mut block = Block::new(0);
+        block.ts_type_alias("Result", vec!["T".to_string(), "E".to_string()], TsType::Union(vec![
+            TsType::Object(vec![TsInterfaceMember::property("ok", TsType::ident("T"))]),
+            TsType::Object(vec![TsInterfaceMember::property("err", TsType::ident("E"))]),
+        ])).unwrap();
+        block.ts_type_alias("Id", Vec::new(), TsType::Union(vec![TsType::ident("string"), TsType::ident("number")])).unwrap();
+        assert_eq!(
+            block.generate().unwrap(),
+            "type Result<T, E> = { ok: T } | { err: E };\ntype Id = string | number;\n"
+        );
+    }
+
+    #[test]
+    fn test_ts_assertions() {
+        let mut block = Block::new(0);
+        block.ts_assert(Statement::Raw("fn()".to_string()), TsType::ident("SomeType"));
+        block.ts_non_null(Statement::Raw("foo.bar".to_string()));
+        block.ts_non_null(Statement::TsAssertion {
+            expr: Box::new(Statement::Raw("x".to_string())),
+            type_ann: TsType::ident("Foo")
+        });
+        block.ts_assert(Statement::Binary {
+            left: Box::new(Statement::Raw("a".to_string())),
+            operator: "??".to_string(),
+            right: Box::new(Statement::Raw("b".to_string()))
+        }, TsType::Nullable(Box::new(TsType::ident("string"))));
+        assert_eq!(
+            block.generate().unwrap(),
+            "fn() as SomeType;\nfoo.bar!;\n(x as Foo)!;\n(a ?? b) as string | null;\n"
+        );
+    }
+
+    #[test]
+    fn test_ts_declare() {
+        let mut module_body = Block::new(0);
+        module_body.ts_declare(Statement::Raw("function foo(): void".to_string()));
+        module_body.typed_var_decl(VarType::Const, "version", TsType::ident("string"), None).unwrap();
+
+        let mut block = Block::new(0);
+        block.ts_declare(Statement::var_decl(VarType::Const, "x", Some(Statement::Literal { value: "5".to_string() })));
+        block.ts_declare_module("foo", module_body);
+        block.ts_declare_module("bar", Block::new(0));
+        assert_eq!(
+            block.generate().unwrap(),
+            concat!(
+                "declare const x = 5;\n",
+                "declare module 'foo' {\n",
+                "    declare function foo(): void\n",
+                "    const version: string;\n",
+                "}\n",
+                "declare module 'bar' {}\n"
+            )
+        );
+        let options = CodegenOptions::new().with_quote_style(QuoteStyle::Backtick);
+        assert_eq!(
+            Statement::TsDeclareModule { name: "bar".to_string(), body: Box::new(Block::new(0)) }.generate_with_options(&options).unwrap(),
+            "declare module 'bar' {}"
+        );
+
+        let result = Statement::TsDeclare(Box::new(Statement::Null)).generate();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_call_type_args() {
+        let call = Statement::Identifier("identity".to_string())
+            .call_with_type_args(vec![TsType::ident("string")], vec!["hello".into()]);
+        assert_eq!(call.generate().unwrap(), "identity<string>('hello')");
+
+        let call = Statement::Identifier("map".to_string())
+            .call(Vec::new())
+            .with_type_args(vec![TsType::ident("K"), TsType::Array(Box::new(TsType::ident("V")))])
+            .unwrap();
+        assert_eq!(call.generate().unwrap(), "map<K, V[]>()");
+        assert_eq!(call.generate_with_options(&CodegenOptions::new().with_minify(true)).unwrap(), "map<K,V[]>()");
+        assert!(Statement::Null.with_type_args(Vec::new()).is_err());
+    }
+}
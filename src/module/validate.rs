@@ -0,0 +1,37 @@
+use crate::codegen::CodegenError;
+
+/// Words that cannot be used as identifiers (ECMAScript 2023, including strict mode reserved words).
+pub const RESERVED_WORDS: &[&str] = &[
+    "await", "break", "case", "catch", "class", "const", "continue", "debugger", "default",
+    "delete", "do", "else", "enum", "export", "extends", "false", "finally", "for", "function",
+    "if", "implements", "import", "in", "instanceof", "interface", "let", "new", "null", "package",
+    "private", "protected", "public", "return", "static", "super", "switch", "this", "throw",
+    "true", "try", "typeof", "var", "void", "while", "with", "yield"
+];
+
+/// Check that the name is a valid JavaScript identifier: it must start with a letter, `$` or `_`,
+/// contain only letters, digits, `$` and `_`, and must not be a reserved word.
+pub fn validate_identifier(name: &str) -> Result<(), CodegenError> {
+    let mut chars = name.chars();
+    let valid_start = chars.next().is_some_and(|c| c.is_alphabetic() || c == '$' || c == '_');
+    let valid_rest = chars.all(|c| c.is_alphanumeric() || c == '$' || c == '_');
+    if !valid_start || !valid_rest || RESERVED_WORDS.contains(&name) {
+        return Err(CodegenError::InvalidIdentifier(name.to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::module::validate::validate_identifier;
+
+    #[test]
+    fn test_validate_identifier() {
+        for name in ["foo", "_bar", "$", "camelCase2", "zażółć"] {
+            assert!(validate_identifier(name).is_ok(), "`{}` should be valid", name);
+        }
+        for name in ["", "2fast", "foo-bar", "foo bar", "class", "implements", "let", "yield"] {
+            assert!(validate_identifier(name).is_err(), "`{}` should be invalid", name);
+        }
+    }
+}